@@ -50,7 +50,7 @@ mod app {
         let mut motor_pwm = motor_pwm.with(gpiob.pb10);
         motor_pwm.enable();
         let mut motor = Motor::new(motor_in1, motor_in2, motor_pwm).unwrap();
-        motor.drive(DriveCommand::Backward(0)).unwrap();
+        motor.drive(DriveCommand::backward_const(0)).unwrap();
 
         // set up the button
         let mut button = gpioc.pc13.into_pull_down_input();
@@ -93,27 +93,31 @@ mod app {
         ctx.shared.motor.lock(|motor| {
             let motor_ramp_direction = ctx.local.motor_ramp_direction;
             let new_drive_direction = match motor.current_drive_command() {
-                DriveCommand::Forward(speed) => match speed {
+                DriveCommand::Forward(speed) => match speed.get() {
                     100 => {
                         *motor_ramp_direction = -1;
-                        DriveCommand::Forward(99)
+                        DriveCommand::forward_const(99)
                     }
                     0 => {
                         *motor_ramp_direction = 1;
-                        DriveCommand::Backward(1)
+                        DriveCommand::backward_const(1)
+                    }
+                    speed => {
+                        DriveCommand::forward_const((speed as i8 + *motor_ramp_direction) as u8)
                     }
-                    _ => DriveCommand::Forward((*speed as i8 + *motor_ramp_direction) as u8),
                 },
-                DriveCommand::Backward(speed) => match speed {
+                DriveCommand::Backward(speed) => match speed.get() {
                     100 => {
                         *motor_ramp_direction = -1;
-                        DriveCommand::Backward(99)
+                        DriveCommand::backward_const(99)
                     }
                     0 => {
                         *motor_ramp_direction = 1;
-                        DriveCommand::Forward(1)
+                        DriveCommand::forward_const(1)
+                    }
+                    speed => {
+                        DriveCommand::backward_const((speed as i8 + *motor_ramp_direction) as u8)
                     }
-                    _ => DriveCommand::Backward((*speed as i8 + *motor_ramp_direction) as u8),
                 },
                 DriveCommand::Stop | DriveCommand::Brake => {
                     return;
@@ -141,7 +145,7 @@ mod app {
                 }
                 DriveCommand::Brake => {
                     defmt::info!("brake was on => starting the motor again");
-                    motor.drive(DriveCommand::Backward(0)).unwrap();
+                    motor.drive(DriveCommand::backward_const(0)).unwrap();
                     update_motor_speed::spawn_after(100.millis()).ok();
                 }
                 _ => {
@@ -9,6 +9,17 @@
 //! * You plan on using both motors without the standby feature: use two separate [`Motor`]s
 //! * You plan on using a single motor with the standby feature: use [`Motor`] and control the standby pin manually
 //! * You plan on using a single motor without the standby feature: use [`Motor`]
+//!
+//! ## Usage with async executors
+//! `embedded-hal-async` does not define async variants of [`OutputPin`] or [`SetDutyCycle`]: setting a GPIO or a PWM
+//! duty cycle is a plain register write which completes immediately, so there is nothing to `.await`.
+//! HALs for async frameworks like [Embassy](https://embassy.dev) thus implement the blocking `embedded-hal` traits for
+//! their GPIO & PWM types, which means that [`Motor`] and [`Tb6612fng`] can be used as-is from async tasks without
+//! blocking the executor.
+//!
+//! The only exceptions are the methods which wait for a duration using a blocking [`DelayNs`] (e.g.
+//! [`Motor::drive_for`]). Enable the `async-hal` feature and use `AsyncMotor` (resp. `AsyncTb6612fng` for both
+//! motors) to `.await` an async `embedded_hal_async::delay::DelayNs` instead.
 
 #![forbid(unsafe_code)]
 #![deny(warnings)]
@@ -17,15 +28,42 @@
 #![deny(unused)]
 #![no_std]
 
+use core::cell::RefCell;
 use core::error::Error;
 use core::fmt::{Debug, Formatter};
+use embedded_hal::delay::DelayNs;
 use embedded_hal::digital::{OutputPin, StatefulOutputPin};
-use embedded_hal::pwm::SetDutyCycle;
+use embedded_hal::pwm::{ErrorType, SetDutyCycle};
+
+#[cfg(feature = "test-util")]
+pub mod test_util;
+
+/// Log a debug message using `defmt` or `log`, whichever is enabled. If both features are enabled only `defmt` is used.
+macro_rules! log_debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "defmt")]
+        defmt::debug!($($arg)*);
+        #[cfg(all(feature = "log", not(feature = "defmt")))]
+        log::debug!($($arg)*);
+    };
+}
+
+/// Log a warning using `defmt` or `log`, whichever is enabled. If both features are enabled only `defmt` is used.
+macro_rules! log_warn {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "defmt")]
+        defmt::warn!($($arg)*);
+        #[cfg(all(feature = "log", not(feature = "defmt")))]
+        log::warn!($($arg)*);
+    };
+}
 
 /// Defines errors which can happen when calling [`Motor::drive()`].
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MotorError<IN1Error, IN2Error, PWMError> {
-    /// An invalid speed has been defined. The speed must be given as a percentage value between 0 and 100 to be valid.
+    /// An invalid speed has been defined. This can only happen with raw speed inputs (e.g. [`Motor::drive_signed()`],
+    /// [`Motor::drive_raw()`] or [`Motor::drive_permille()`]) as a [`DriveCommand`] always carries a valid [`Percent`].
     InvalidSpeed,
     /// An error in setting the output of the IN1 pin
     In1Error(IN1Error),
@@ -49,6 +87,40 @@ impl<IN1Error: Debug, IN2Error: Debug, PWMError: Debug> core::fmt::Display
     }
 }
 
+#[cfg(feature = "ufmt")]
+impl<IN1Error, IN2Error, PWMError> ufmt::uDisplay for MotorError<IN1Error, IN2Error, PWMError> {
+    fn fmt<W: ufmt::uWrite + ?Sized>(
+        &self,
+        f: &mut ufmt::Formatter<'_, W>,
+    ) -> Result<(), W::Error> {
+        use MotorError::*;
+        match self {
+            InvalidSpeed => f.write_str("an invalid speed has been specified"),
+            In1Error(_) => f.write_str("failed to set the output of the IN1 pin"),
+            In2Error(_) => f.write_str("failed to set the output of the IN2 pin"),
+            PwmError(_) => f.write_str("failed to set the output of the PWM pin"),
+        }
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl<IN1Error: ufmt::uDebug, IN2Error: ufmt::uDebug, PWMError: ufmt::uDebug> ufmt::uDebug
+    for MotorError<IN1Error, IN2Error, PWMError>
+{
+    fn fmt<W: ufmt::uWrite + ?Sized>(
+        &self,
+        f: &mut ufmt::Formatter<'_, W>,
+    ) -> Result<(), W::Error> {
+        use MotorError::*;
+        match self {
+            InvalidSpeed => f.write_str("InvalidSpeed"),
+            In1Error(e) => f.debug_tuple("In1Error")?.field(e)?.finish(),
+            In2Error(e) => f.debug_tuple("In2Error")?.field(e)?.finish(),
+            PwmError(e) => f.debug_tuple("PwmError")?.field(e)?.finish(),
+        }
+    }
+}
+
 impl<
         IN1Error: Debug + Error + 'static,
         IN2Error: Debug + Error + 'static,
@@ -66,8 +138,31 @@ impl<
     }
 }
 
+impl<IN1Error, IN2Error, PWMError> From<InvalidSpeedError>
+    for MotorError<IN1Error, IN2Error, PWMError>
+{
+    fn from(_: InvalidSpeedError) -> Self {
+        MotorError::InvalidSpeed
+    }
+}
+
+/// Defines the error which happens when trying to create a [`DriveCommand`] from an invalid speed.
+/// The speed must be given as a percentage value between 0 and 100 to be valid.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InvalidSpeedError;
+
+impl core::fmt::Display for InvalidSpeedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "an invalid speed has been specified")
+    }
+}
+
+impl Error for InvalidSpeedError {}
+
 /// Defines errors which can happen when calling [`Tb6612fng::new()`].
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Tb6612fngError<STBYError> {
     /// An error in setting the initial output of the standby pin
     Standby(STBYError),
@@ -82,6 +177,32 @@ impl<STBYError: Debug> core::fmt::Display for Tb6612fngError<STBYError> {
     }
 }
 
+#[cfg(feature = "ufmt")]
+impl<STBYError> ufmt::uDisplay for Tb6612fngError<STBYError> {
+    fn fmt<W: ufmt::uWrite + ?Sized>(
+        &self,
+        f: &mut ufmt::Formatter<'_, W>,
+    ) -> Result<(), W::Error> {
+        use Tb6612fngError::*;
+        match self {
+            Standby(_) => f.write_str("failed to set the output of the standby pin"),
+        }
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl<STBYError: ufmt::uDebug> ufmt::uDebug for Tb6612fngError<STBYError> {
+    fn fmt<W: ufmt::uWrite + ?Sized>(
+        &self,
+        f: &mut ufmt::Formatter<'_, W>,
+    ) -> Result<(), W::Error> {
+        use Tb6612fngError::*;
+        match self {
+            Standby(e) => f.debug_tuple("Standby")?.field(e)?.finish(),
+        }
+    }
+}
+
 impl<STBYError: Debug + Error + 'static> Error for Tb6612fngError<STBYError> {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         use Tb6612fngError::*;
@@ -91,176 +212,966 @@ impl<STBYError: Debug + Error + 'static> Error for Tb6612fngError<STBYError> {
     }
 }
 
-/// Defines the possible drive commands.
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
-pub enum DriveCommand {
-    /// Drive forward with the defined speed (in percentage)
-    Forward(u8),
-    /// Drive backward with the defined speed (in percentage)
-    Backward(u8),
-    /// Actively brake
-    Brake,
-    /// Coast, i.e. stop but don't actively brake.
-    Stop,
+/// Defines errors which can happen when calling [`Tb6612fng::drive()`].
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Tb6612fngDriveError<MotorAError, MotorBError> {
+    /// An error in driving motor A
+    MotorA(MotorAError),
+    /// An error in driving motor B
+    MotorB(MotorBError),
+    /// The motor has not been driven because the controller is in standby,
+    /// see [`Tb6612fng::drive_motor_a`] and [`Tb6612fng::drive_motor_b`].
+    InStandby,
+    /// An invalid speed has been specified, see [`Tb6612fng::differential_drive`]. No motor has been driven.
+    InvalidSpeed,
 }
 
-/// Represents a TB6612FNG controller.
+impl<MotorAError: Debug, MotorBError: Debug> core::fmt::Display
+    for Tb6612fngDriveError<MotorAError, MotorBError>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        use Tb6612fngDriveError::*;
+        match self {
+            MotorA(_) => write!(f, "failed to drive motor A"),
+            MotorB(_) => write!(f, "failed to drive motor B"),
+            InStandby => write!(f, "the controller is in standby"),
+            InvalidSpeed => write!(f, "an invalid speed has been specified"),
+        }
+    }
+}
+
+impl<MotorAError: Debug + Error + 'static, MotorBError: Debug + Error + 'static> Error
+    for Tb6612fngDriveError<MotorAError, MotorBError>
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use Tb6612fngDriveError::*;
+        match self {
+            MotorA(e) => Some(e),
+            MotorB(e) => Some(e),
+            InStandby | InvalidSpeed => None,
+        }
+    }
+}
+
+/// Defines errors which can happen when operating on both motors and the standby pin of a [`Tb6612fng`] at the same
+/// time, e.g. when calling [`Tb6612fng::emergency_stop()`].
 ///
-/// Use the [`Motor`] struct directly if you only have one motor.
-/// See the crate-level comment for further details on when to use what.
-#[derive(Debug)]
-pub struct Tb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY> {
-    /// The first motor, labelled as 'A' on the chip
-    pub motor_a: Motor<MAIN1, MAIN2, MAPWM>,
-    /// The second motor, labelled as 'B' on the chip
-    pub motor_b: Motor<MBIN1, MBIN2, MBPWM>,
-    /// The standby pin used to put both motors on standby
-    standby: STBY,
+/// This covers all errors of a [`Tb6612fng`] and can thus be used as the single error type of an application using
+/// it (see [`ControllerError`]): [`Tb6612fngError`] and [`Tb6612fngDriveError`] can be converted into it (e.g. with
+/// `?`), errors of individual motors or of the standby pin can be wrapped with e.g.
+/// `.map_err(Tb6612fngControlError::MotorA)`.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Tb6612fngControlError<MotorAError, MotorBError, STBYError> {
+    /// An error in driving motor A
+    MotorA(MotorAError),
+    /// An error in driving motor B
+    MotorB(MotorBError),
+    /// An error in setting the output of the standby pin
+    Standby(STBYError),
+    /// The motor has not been driven because the controller is in standby, see [`Tb6612fngDriveError::InStandby`].
+    InStandby,
+    /// An invalid speed has been specified, see [`Tb6612fngDriveError::InvalidSpeed`]. No motor has been driven.
+    InvalidSpeed,
 }
 
-impl<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>
-    Tb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>
-where
-    MAIN1: OutputPin,
-    MAIN2: OutputPin,
-    MAPWM: SetDutyCycle,
-    MBIN1: OutputPin,
-    MBIN2: OutputPin,
-    MBPWM: SetDutyCycle,
-    STBY: OutputPin,
+/// The [`Tb6612fngControlError`] for a [`Tb6612fng`] with the defined pins, to avoid having to spell out the error
+/// types of all pins.
+///
+/// # Usage example
+/// ```
+/// # use embedded_hal_mock::eh1::digital::Mock as PinMock;
+/// # use embedded_hal_mock::eh1::pwm::Mock as PwmMock;
+/// # use tb6612fng::{ControllerError, DriveCommand, Tb6612fng};
+/// type Controller = Tb6612fng<PinMock, PinMock, PwmMock, PinMock, PinMock, PwmMock, PinMock>;
+/// type Error = ControllerError<PinMock, PinMock, PwmMock, PinMock, PinMock, PwmMock, PinMock>;
+///
+/// fn forward(controller: &mut Controller) -> Result<(), Error> {
+///     controller.disable_standby().map_err(Error::Standby)?;
+///     controller.drive_both(DriveCommand::forward_const(50))?;
+///     Ok(())
+/// }
+/// ```
+pub type ControllerError<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY> = Tb6612fngControlError<
+    MotorError<
+        <MAIN1 as embedded_hal::digital::ErrorType>::Error,
+        <MAIN2 as embedded_hal::digital::ErrorType>::Error,
+        <MAPWM as ErrorType>::Error,
+    >,
+    MotorError<
+        <MBIN1 as embedded_hal::digital::ErrorType>::Error,
+        <MBIN2 as embedded_hal::digital::ErrorType>::Error,
+        <MBPWM as ErrorType>::Error,
+    >,
+    <STBY as embedded_hal::digital::ErrorType>::Error,
+>;
+
+impl<MotorAError: Debug, MotorBError: Debug, STBYError: Debug> core::fmt::Display
+    for Tb6612fngControlError<MotorAError, MotorBError, STBYError>
 {
-    /// Instantiate a new [`Tb6612fng`] with the defined pins.
-    /// This also automatically enables the two PWM pins.
-    /// The initial state of the motors will be set to [stopped](DriveCommand::Stop).
-    /// The initial state of standby will be *disabled*.
-    ///
-    /// # Errors
-    /// If any of the underlying pin interactions fail these errors will be propagated up.
-    /// The errors are specific to your HAL.
-    ///
-    /// # Usage example
-    /// ```
-    ///
-    /// # fn main() -> Result<(), Box<dyn core::error::Error>> {
-    /// # use embedded_hal_mock::eh1::digital::Mock as PinMock;
-    /// # use embedded_hal_mock::eh1::pwm::Mock as PwmMock;
-    /// # use embedded_hal_mock::eh1::pwm::Transaction as PwmTransaction;
-    /// # use embedded_hal_mock::eh1::digital::Transaction as PinTransaction;
-    /// # use embedded_hal_mock::eh1::digital::State::{High, Low};
-    ///
-    /// # let motor_a_in1 = PinMock::new(&[PinTransaction::set(Low)]);
-    /// # let mut motor_a_in1_ = motor_a_in1.clone();
-    /// # let motor_a_in2 = PinMock::new(&[PinTransaction::set(Low)]);
-    /// # let mut motor_a_in2_ = motor_a_in2.clone();
-    /// # let motor_a_pwm = PwmMock::new(&[PwmTransaction::max_duty_cycle(100), PwmTransaction::set_duty_cycle(0)]);
-    /// # let mut motor_a_pwm_ = motor_a_pwm.clone();
-    ///
-    /// # let motor_b_in1 = PinMock::new(&[PinTransaction::set(Low)]);
-    /// # let mut motor_b_in1_ = motor_b_in1.clone();
-    /// # let motor_b_in2 = PinMock::new(&[PinTransaction::set(Low)]);
-    /// # let mut motor_b_in2_ = motor_b_in2.clone();
-    /// # let motor_b_pwm = PwmMock::new(&[PwmTransaction::max_duty_cycle(100), PwmTransaction::set_duty_cycle(0)]);
-    /// # let mut motor_b_pwm_ = motor_b_pwm.clone();
-    ///
-    /// # let standby = PinMock::new(&[PinTransaction::set(High)]);
-    /// # let mut standby_ = standby.clone();
-    ///
-    /// use tb6612fng::{Motor, Tb6612fng};
-    /// let controller = Tb6612fng::new(
-    ///     Motor::new(motor_a_in1, motor_a_in2, motor_a_pwm)?,
-    ///     Motor::new(motor_b_in1, motor_b_in2, motor_b_pwm)?,
-    ///     standby,
-    /// );
-    ///
-    /// # motor_a_in1_.done();
-    /// # motor_a_in2_.done();
-    /// # motor_a_pwm_.done();
-    /// # motor_b_in1_.done();
-    /// # motor_b_in2_.done();
-    /// # motor_b_pwm_.done();
-    /// # standby_.done();
-    /// # Ok(())
-    /// # }
-    /// ```
-    #[allow(clippy::type_complexity)]
-    pub fn new(
-        motor_a: Motor<MAIN1, MAIN2, MAPWM>,
-        motor_b: Motor<MBIN1, MBIN2, MBPWM>,
-        standby: STBY,
-    ) -> Result<
-        Tb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>,
-        Tb6612fngError<STBY::Error>,
-    > {
-        let mut controller = Tb6612fng {
-            motor_a,
-            motor_b,
-            standby,
-        };
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        use Tb6612fngControlError::*;
+        match self {
+            MotorA(_) => write!(f, "failed to drive motor A"),
+            MotorB(_) => write!(f, "failed to drive motor B"),
+            Standby(_) => write!(f, "failed to set the output of the standby pin"),
+            InStandby => write!(f, "the controller is in standby"),
+            InvalidSpeed => write!(f, "an invalid speed has been specified"),
+        }
+    }
+}
 
-        controller
-            .disable_standby()
-            .map_err(Tb6612fngError::Standby)?;
+impl<
+        MotorAError: Debug + Error + 'static,
+        MotorBError: Debug + Error + 'static,
+        STBYError: Debug + Error + 'static,
+    > Error for Tb6612fngControlError<MotorAError, MotorBError, STBYError>
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use Tb6612fngControlError::*;
+        match self {
+            MotorA(e) => Some(e),
+            MotorB(e) => Some(e),
+            Standby(e) => Some(e),
+            InStandby | InvalidSpeed => None,
+        }
+    }
+}
 
-        Ok(controller)
+impl<MotorAError, MotorBError, STBYError> From<Tb6612fngError<STBYError>>
+    for Tb6612fngControlError<MotorAError, MotorBError, STBYError>
+{
+    fn from(error: Tb6612fngError<STBYError>) -> Self {
+        match error {
+            Tb6612fngError::Standby(e) => Tb6612fngControlError::Standby(e),
+        }
     }
+}
 
-    /// Enable standby. This ignores any other setting currently done on the motors and puts them into standby.
-    ///
-    /// Note that this does not change any commands on the motors, i.e. the PWM signal will continue
-    /// and once [`Tb6612fng::disable_standby`] is called the motor will pick up where it left off (unless the command was changed in-between).
-    ///
-    /// # Errors
-    /// If the underlying pin interaction fails this error will be propagated up.
-    /// The error is specific to your HAL.
-    pub fn enable_standby(&mut self) -> Result<(), STBY::Error> {
-        self.standby.set_low()
+impl<MotorAError, MotorBError, STBYError> From<Tb6612fngDriveError<MotorAError, MotorBError>>
+    for Tb6612fngControlError<MotorAError, MotorBError, STBYError>
+{
+    fn from(error: Tb6612fngDriveError<MotorAError, MotorBError>) -> Self {
+        match error {
+            Tb6612fngDriveError::MotorA(e) => Tb6612fngControlError::MotorA(e),
+            Tb6612fngDriveError::MotorB(e) => Tb6612fngControlError::MotorB(e),
+            Tb6612fngDriveError::InStandby => Tb6612fngControlError::InStandby,
+            Tb6612fngDriveError::InvalidSpeed => Tb6612fngControlError::InvalidSpeed,
+        }
     }
+}
 
-    /// Disable standby. Note that the last active commands on the motors will resume.
-    ///
-    /// # Errors
-    /// If the underlying pin interaction fails this error will be propagated up.
-    /// The error is specific to your HAL.
-    pub fn disable_standby(&mut self) -> Result<(), STBY::Error> {
-        self.standby.set_high()
+/// The results of driving both motors independently, see [`Tb6612fng::try_drive_both()`].
+///
+/// Partial success is possible, i.e. one motor might have been driven successfully while the other one failed.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+pub struct Tb6612fngDriveResults<MotorAError, MotorBError> {
+    /// The result of driving motor A
+    pub motor_a: Result<(), MotorAError>,
+    /// The result of driving motor B
+    pub motor_b: Result<(), MotorBError>,
+}
+
+impl<MotorAError, MotorBError> Tb6612fngDriveResults<MotorAError, MotorBError> {
+    /// Returns whether both motors have been driven successfully.
+    pub fn is_ok(&self) -> bool {
+        self.motor_a.is_ok() && self.motor_b.is_ok()
     }
 
-    /// Returns whether the standby mode is enabled.
+    /// Convert this into a single result, reporting the error of motor A if both motors failed.
+    pub fn into_result(self) -> Result<(), Tb6612fngDriveError<MotorAError, MotorBError>> {
+        self.motor_a.map_err(Tb6612fngDriveError::MotorA)?;
+        self.motor_b.map_err(Tb6612fngDriveError::MotorB)
+    }
+}
+
+/// Defines errors which can happen when calling [`MotorArray::drive_all()`].
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MotorArrayDriveError<MotorError> {
+    /// The index of the motor which failed to be driven
+    pub index: usize,
+    /// The error which occurred when driving the motor
+    pub error: MotorError,
+}
+
+impl<MotorError: Debug> core::fmt::Display for MotorArrayDriveError<MotorError> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "failed to drive motor {}", self.index)
+    }
+}
+
+impl<MotorError: Debug + Error + 'static> Error for MotorArrayDriveError<MotorError> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// A speed in percentage which is guaranteed to be between 0 and 100 (inclusive).
+///
+/// This is the speed of [`DriveCommand::Forward`] and [`DriveCommand::Backward`], thus a drive command can never hold
+/// an invalid speed and driving a motor with it can never fail because of the speed.
+///
+/// # Usage example
+/// ```
+/// # use tb6612fng::{DriveCommand, Percent};
+/// const HALF: Percent = Percent::new_const(50);
+/// assert_eq!(DriveCommand::Forward(HALF), DriveCommand::forward_const(50));
+/// assert_eq!(Percent::new(101), None);
+/// ```
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Percent(u8);
+
+impl Percent {
+    /// 0%
+    pub const MIN: Percent = Percent(0);
+    /// 100%
+    pub const MAX: Percent = Percent(100);
+
+    /// Create a new [`Percent`] value, returns `None` if the value is above 100.
+    pub const fn new(percent: u8) -> Option<Percent> {
+        if percent > 100 {
+            None
+        } else {
+            Some(Percent(percent))
+        }
+    }
+
+    /// Create a new [`Percent`] value which is checked at compile time when used in a const context.
     ///
-    /// *NOTE* this does *not* read the electrical state of the pin, see [`StatefulOutputPin`]
+    /// # Panics
+    /// Panics if the value is above 100. In a const context this results in a compile error:
+    /// ```compile_fail
+    /// # use tb6612fng::Percent;
+    /// const TOO_MUCH: Percent = Percent::new_const(101);
+    /// ```
+    pub const fn new_const(percent: u8) -> Percent {
+        assert!(percent <= 100, "the percentage must not be above 100");
+        Percent(percent)
+    }
+
+    /// Create a new [`Percent`] value, values above 100 are treated as 100.
+    pub const fn saturating_new(percent: u8) -> Percent {
+        if percent > 100 {
+            Percent::MAX
+        } else {
+            Percent(percent)
+        }
+    }
+
+    /// Returns the value in percentage.
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+/// A speed in percentage, this is the same as [`Percent`].
+pub type Speed = Percent;
+
+impl TryFrom<u8> for Percent {
+    type Error = InvalidSpeedError;
+
+    /// See [`Percent::new`].
     ///
     /// # Errors
-    /// If the underlying pin interaction fails this error will be propagated up.
-    /// The error is specific to your HAL.
-    pub fn current_standby(&mut self) -> Result<bool, STBY::Error>
-    where
-        STBY: StatefulOutputPin,
-    {
-        self.standby.is_set_high()
+    /// The value must be between 0 and 100 (inclusive), otherwise you will get an [`InvalidSpeedError`].
+    fn try_from(percent: u8) -> Result<Self, Self::Error> {
+        Percent::new(percent).ok_or(InvalidSpeedError)
     }
 }
 
-/// Represents a single motor (either motor A or motor B) hooked up to a TB6612FNG controller.
-///
-/// This is unaware of the standby pin. If you plan on using both motors and the standby feature then use the [`Tb6612fng`] struct instead.
-/// See the crate-level comment for further details on when to use what.
-#[derive(Debug)]
-pub struct Motor<IN1, IN2, PWM> {
-    in1: IN1,
-    in2: IN2,
-    pwm: PWM,
-    current_drive_command: DriveCommand,
+impl From<Percent> for u8 {
+    fn from(percent: Percent) -> Self {
+        percent.get()
+    }
 }
 
-impl<IN1, IN2, PWM> Motor<IN1, IN2, PWM>
-where
-    IN1: OutputPin,
-    IN2: OutputPin,
-    PWM: SetDutyCycle,
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Percent {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let percent = u8::deserialize(deserializer)?;
+        Percent::new(percent).ok_or_else(|| {
+            serde::de::Error::invalid_value(
+                serde::de::Unexpected::Unsigned(percent.into()),
+                &"a percentage between 0 and 100",
+            )
+        })
+    }
+}
+
+/// A speed in percentage which is guaranteed to be between `MIN` and `MAX` (inclusive), e.g. for a motor which must
+/// stay within a specific operating range.
+///
+/// The bounds themselves are checked at compile time: `MIN` must not be above `MAX` and `MAX` must not be above 100.
+/// ```compile_fail
+/// # use tb6612fng::BoundedSpeed;
+/// let speed = BoundedSpeed::<90, 20>::new(50);
+/// ```
+///
+/// # Usage example
+/// ```
+/// # use tb6612fng::BoundedSpeed;
+/// type FanSpeed = BoundedSpeed<20, 90>;
+/// assert_eq!(FanSpeed::new(50).map(u8::from), Some(50));
+/// assert_eq!(FanSpeed::new(10), None);
+/// ```
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BoundedSpeed<const MIN: u8, const MAX: u8>(u8);
+
+impl<const MIN: u8, const MAX: u8> BoundedSpeed<MIN, MAX> {
+    const VALID_BOUNDS: () = assert!(
+        MIN <= MAX && MAX <= 100,
+        "MIN must not be above MAX and MAX must not be above 100"
+    );
+
+    /// Create a new [`BoundedSpeed`], returns `None` if the speed is outside of the bounds.
+    pub const fn new(speed: u8) -> Option<BoundedSpeed<MIN, MAX>> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_BOUNDS;
+
+        if speed < MIN || speed > MAX {
+            None
+        } else {
+            Some(BoundedSpeed(speed))
+        }
+    }
+
+    /// Create a new [`BoundedSpeed`], speeds outside of the bounds are clamped to them.
+    pub const fn saturating_new(speed: u8) -> BoundedSpeed<MIN, MAX> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_BOUNDS;
+
+        if speed < MIN {
+            BoundedSpeed(MIN)
+        } else if speed > MAX {
+            BoundedSpeed(MAX)
+        } else {
+            BoundedSpeed(speed)
+        }
+    }
+
+    /// Returns the speed in percentage.
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl<const MIN: u8, const MAX: u8> From<BoundedSpeed<MIN, MAX>> for u8 {
+    fn from(speed: BoundedSpeed<MIN, MAX>) -> Self {
+        speed.get()
+    }
+}
+
+impl<const MIN: u8, const MAX: u8> From<BoundedSpeed<MIN, MAX>> for Percent {
+    fn from(speed: BoundedSpeed<MIN, MAX>) -> Self {
+        // the bounds guarantee that the speed is valid
+        Percent(speed.get())
+    }
+}
+
+/// Defines the possible drive commands.
+///
+/// The default is [`DriveCommand::Stop`], which is also the initial state of a [`Motor`].
+///
+/// The speed is given as a [`Percent`], which guarantees that it is valid:
+/// ```
+/// # use tb6612fng::{DriveCommand, Percent};
+/// let half = DriveCommand::Forward(Percent::new_const(50));
+/// assert_eq!(half, DriveCommand::forward_const(50));
+/// ```
+///
+/// If the `serde` feature is enabled drive commands can be serialized and deserialized, e.g. to receive them over a
+/// serial protocol. Any `serde` format can be used, e.g. JSON on embedded Linux (invalid speeds are rejected when
+/// deserializing):
+/// ```
+/// # #[cfg(feature = "serde")] {
+/// # use tb6612fng::DriveCommand;
+/// let json = serde_json::to_string(&DriveCommand::forward_const(50)).unwrap();
+/// assert_eq!(json, r#"{"Forward":50}"#);
+/// assert_eq!(serde_json::from_str::<DriveCommand>(&json).unwrap(), DriveCommand::forward_const(50));
+/// assert!(serde_json::from_str::<DriveCommand>(r#"{"Forward":101}"#).is_err());
+/// # }
+/// ```
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DriveCommand {
+    /// Drive forward with the defined speed
+    Forward(Percent),
+    /// Drive backward with the defined speed
+    Backward(Percent),
+    /// Actively brake
+    Brake,
+    /// Coast, i.e. stop but don't actively brake.
+    #[default]
+    Stop,
+}
+
+impl DriveCommand {
+    /// Drive forward with full speed.
+    pub const FORWARD_MAX: DriveCommand = DriveCommand::Forward(Percent::MAX);
+    /// Drive forward with a speed of 0.
+    pub const FORWARD_STOPPED: DriveCommand = DriveCommand::Forward(Percent::MIN);
+    /// Drive backward with full speed.
+    pub const BACKWARD_MAX: DriveCommand = DriveCommand::Backward(Percent::MAX);
+    /// Drive backward with a speed of 0.
+    pub const BACKWARD_STOPPED: DriveCommand = DriveCommand::Backward(Percent::MIN);
+
+    /// Create a [`DriveCommand::Forward`] with the defined speed, the same as using the variant directly.
+    pub const fn forward(speed: Percent) -> DriveCommand {
+        DriveCommand::Forward(speed)
+    }
+
+    /// Create a [`DriveCommand::Backward`] with the defined speed, the same as using the variant directly.
+    pub const fn backward(speed: Percent) -> DriveCommand {
+        DriveCommand::Backward(speed)
+    }
+
+    /// Create a [`DriveCommand::Forward`] with the defined speed (in percentage) which is checked at compile time
+    /// when used in a const context. This allows you to define your own named constants.
+    ///
+    /// # Panics
+    /// Panics if the speed is above 100. In a const context this results in a compile error:
+    /// ```compile_fail
+    /// # use tb6612fng::DriveCommand;
+    /// const TOO_FAST: DriveCommand = DriveCommand::forward_const(101);
+    /// ```
+    ///
+    /// # Usage example
+    /// ```
+    /// # use tb6612fng::DriveCommand;
+    /// const HALF: DriveCommand = DriveCommand::forward_const(50);
+    /// assert_eq!(HALF.speed(), Some(50));
+    /// ```
+    pub const fn forward_const(speed: u8) -> DriveCommand {
+        assert!(speed <= 100, "the speed must not be above 100");
+        DriveCommand::Forward(Percent(speed))
+    }
+
+    /// Create a [`DriveCommand::Backward`] with the defined speed (in percentage) which is checked at compile time
+    /// when used in a const context. This allows you to define your own named constants.
+    ///
+    /// # Panics
+    /// Panics if the speed is above 100. In a const context this results in a compile error:
+    /// ```compile_fail
+    /// # use tb6612fng::DriveCommand;
+    /// const TOO_FAST: DriveCommand = DriveCommand::backward_const(101);
+    /// ```
+    ///
+    /// # Usage example
+    /// ```
+    /// # use tb6612fng::DriveCommand;
+    /// const HALF: DriveCommand = DriveCommand::backward_const(50);
+    /// assert_eq!(HALF.speed(), Some(50));
+    /// ```
+    pub const fn backward_const(speed: u8) -> DriveCommand {
+        assert!(speed <= 100, "the speed must not be above 100");
+        DriveCommand::Backward(Percent(speed))
+    }
+
+    /// Create a [`DriveCommand::Forward`] with the defined speed (in percentage) without checking it at compile time,
+    /// e.g. for speeds which are computed in a const context.
+    ///
+    /// Speeds above 100 are treated as 100 as a [`DriveCommand`] can never hold an invalid speed. They are considered a
+    /// bug though and panic if debug assertions are enabled, use [`DriveCommand::saturating_with_speed`] if you
+    /// actually want to saturate the speed.
+    ///
+    /// # Usage example
+    /// ```
+    /// # use tb6612fng::DriveCommand;
+    /// const BASE_SPEED: u8 = 40;
+    /// const FAST: DriveCommand = DriveCommand::forward_unchecked(BASE_SPEED * 2);
+    /// assert_eq!(FAST, DriveCommand::forward_const(80));
+    /// ```
+    pub const fn forward_unchecked(speed: u8) -> DriveCommand {
+        debug_assert!(speed <= 100, "the speed must not be above 100");
+        DriveCommand::Forward(Percent::saturating_new(speed))
+    }
+
+    /// Create a [`DriveCommand::Backward`] with the defined speed (in percentage) without checking it at compile
+    /// time, see [`DriveCommand::forward_unchecked`].
+    pub const fn backward_unchecked(speed: u8) -> DriveCommand {
+        debug_assert!(speed <= 100, "the speed must not be above 100");
+        DriveCommand::Backward(Percent::saturating_new(speed))
+    }
+
+    /// Create a drive command from a signed speed (in percentage): positive values drive forward, negative values
+    /// drive backward and 0 [stops](DriveCommand::Stop) the motor.
+    ///
+    /// # Errors
+    /// The specified speed must be between -100 and 100 (inclusive), otherwise you will get an [`InvalidSpeedError`].
+    pub fn from_signed_speed(speed: i8) -> Result<DriveCommand, InvalidSpeedError> {
+        match speed {
+            -100..=-1 => Ok(DriveCommand::Backward(Percent(speed.unsigned_abs()))),
+            0 => Ok(DriveCommand::Stop),
+            1..=100 => Ok(DriveCommand::Forward(Percent(speed as u8))),
+            _ => Err(InvalidSpeedError),
+        }
+    }
+
+    /// Create a drive command from a signed speed like [`DriveCommand::from_signed_speed`], but saturate speeds
+    /// outside of -100..=100 to full speed in the respective direction instead of rejecting them.
+    ///
+    /// Like [`DriveCommand::from_signed_speed`] this maps 0 to [`DriveCommand::Stop`], i.e. the motor coasts. If you
+    /// want it to be held in place instead replace the resulting `Stop` with [`DriveCommand::Brake`].
+    ///
+    /// ```
+    /// # use tb6612fng::DriveCommand;
+    /// assert_eq!(DriveCommand::from_signed_speed_saturating(127), DriveCommand::FORWARD_MAX);
+    /// assert_eq!(DriveCommand::from_signed_speed_saturating(-42), DriveCommand::backward_const(42));
+    /// assert_eq!(DriveCommand::from_signed_speed_saturating(0), DriveCommand::Stop);
+    /// ```
+    #[doc(alias = "from_i8")]
+    pub fn from_signed_speed_saturating(speed: i8) -> DriveCommand {
+        DriveCommand::from_signed_speed_clamped(speed.into())
+    }
+
+    /// Returns the next drive command when moving from the current (signed) speed towards the (valid) target by at
+    /// most `step` percent, and whether the target has been reached with it.
+    fn ramp_step(current_speed: i8, target: DriveCommand, step: u8) -> (DriveCommand, bool) {
+        let current_speed = i16::from(current_speed);
+        let target_speed = i16::from(target.signed_speed());
+        let step = i16::from(step);
+
+        if (target_speed - current_speed).abs() <= step {
+            return (target, true);
+        }
+
+        let new_speed = if target_speed > current_speed {
+            current_speed + step
+        } else {
+            current_speed - step
+        };
+        (DriveCommand::from_signed_speed_clamped(new_speed), false)
+    }
+
+    /// Create a drive command from a signed speed like [`DriveCommand::from_signed_speed`], but clamp the speed to
+    /// -100..=100 instead of rejecting it.
+    fn from_signed_speed_clamped(speed: i16) -> DriveCommand {
+        match speed.clamp(-100, 100) {
+            s if s < 0 => DriveCommand::Backward(Percent(s.unsigned_abs() as u8)),
+            0 => DriveCommand::Stop,
+            s => DriveCommand::Forward(Percent(s as u8)),
+        }
+    }
+
+    /// Returns the speed (in percentage) if this is a [`DriveCommand::Forward`] or [`DriveCommand::Backward`] command,
+    /// `None` otherwise.
+    pub fn speed(&self) -> Option<u8> {
+        match self {
+            DriveCommand::Forward(s) | DriveCommand::Backward(s) => Some(s.get()),
+            DriveCommand::Brake | DriveCommand::Stop => None,
+        }
+    }
+
+    /// Returns the speed (in percentage) regardless of the direction. [`DriveCommand::Brake`] and
+    /// [`DriveCommand::Stop`] return 0.
+    pub fn magnitude(&self) -> u8 {
+        self.speed().unwrap_or(0)
+    }
+
+    /// Returns the command with the same direction but the defined speed (in percentage).
+    /// [`DriveCommand::Brake`] and [`DriveCommand::Stop`] have no speed and are thus returned unchanged.
+    ///
+    /// # Errors
+    /// The specified speed must be between 0 and 100 (inclusive), otherwise you will get an [`InvalidSpeedError`].
+    pub const fn with_speed(self, speed: u8) -> Result<DriveCommand, InvalidSpeedError> {
+        if speed > 100 {
+            return Err(InvalidSpeedError);
+        }
+
+        Ok(self.saturating_with_speed(speed))
+    }
+
+    /// Returns the command with the same direction but the defined speed (in percentage), treating speeds
+    /// above 100 as 100. [`DriveCommand::Brake`] and [`DriveCommand::Stop`] have no speed and are thus returned
+    /// unchanged.
+    pub const fn saturating_with_speed(self, speed: u8) -> DriveCommand {
+        let speed = Percent::saturating_new(speed);
+
+        match self {
+            DriveCommand::Forward(_) => DriveCommand::Forward(speed),
+            DriveCommand::Backward(_) => DriveCommand::Backward(speed),
+            DriveCommand::Brake => DriveCommand::Brake,
+            DriveCommand::Stop => DriveCommand::Stop,
+        }
+    }
+
+    /// Returns the command with the same direction but the speed clamped to `min..=max`.
+    /// [`DriveCommand::Brake`] and [`DriveCommand::Stop`] have no speed and are thus returned unchanged.
+    ///
+    /// # Panics
+    /// Panics if `min > max` (like [`Ord::clamp`]).
+    pub const fn clamp_speed(self, min: u8, max: u8) -> DriveCommand {
+        assert!(min <= max, "min must not be greater than max");
+
+        match self {
+            DriveCommand::Forward(s) if s.get() < min => self.saturating_with_speed(min),
+            DriveCommand::Backward(s) if s.get() < min => self.saturating_with_speed(min),
+            DriveCommand::Forward(s) | DriveCommand::Backward(s) if s.get() > max => {
+                self.saturating_with_speed(max)
+            }
+            c => c,
+        }
+    }
+
+    /// Returns the command for the opposite direction with the same speed, i.e. [`DriveCommand::Forward`] becomes
+    /// [`DriveCommand::Backward`] and vice versa. [`DriveCommand::Brake`] and [`DriveCommand::Stop`] are returned
+    /// unchanged.
+    ///
+    /// This is also available via the `-` operator.
+    pub const fn reverse(self) -> DriveCommand {
+        match self {
+            DriveCommand::Forward(s) => DriveCommand::Backward(s),
+            DriveCommand::Backward(s) => DriveCommand::Forward(s),
+            DriveCommand::Brake => DriveCommand::Brake,
+            DriveCommand::Stop => DriveCommand::Stop,
+        }
+    }
+
+    /// Returns the signed speed (in percentage) of the drive command: driving forward returns a positive number
+    /// while driving backward returns a negative number and both [`DriveCommand::Brake`] and [`DriveCommand::Stop`]
+    /// return 0.
+    ///
+    /// This is the same mapping as used by [`Motor::current_speed`], which allows comparing commands by their
+    /// effective speed.
+    pub const fn signed_speed(&self) -> i8 {
+        match *self {
+            DriveCommand::Forward(s) => s.get() as i8,
+            DriveCommand::Backward(s) => -(s.get() as i8),
+            DriveCommand::Brake | DriveCommand::Stop => 0,
+        }
+    }
+
+    /// Returns the direction if this is a [`DriveCommand::Forward`] or [`DriveCommand::Backward`] command (regardless
+    /// of its speed), `None` otherwise.
+    pub const fn direction(&self) -> Option<Direction> {
+        match self {
+            DriveCommand::Forward(_) => Some(Direction::Forward),
+            DriveCommand::Backward(_) => Some(Direction::Backward),
+            DriveCommand::Brake | DriveCommand::Stop => None,
+        }
+    }
+
+    /// Returns whether this is a [`DriveCommand::Forward`] command (regardless of its speed).
+    pub const fn is_forward(&self) -> bool {
+        matches!(self, DriveCommand::Forward(_))
+    }
+
+    /// Returns whether this is a [`DriveCommand::Backward`] command (regardless of its speed).
+    pub const fn is_backward(&self) -> bool {
+        matches!(self, DriveCommand::Backward(_))
+    }
+
+    /// Returns whether this command moves the motor, i.e. drives forward or backward with a speed above 0.
+    ///
+    /// This is e.g. useful to detect idle motors: driving with a speed of 0 is not considered to be moving.
+    /// ```
+    /// # use tb6612fng::DriveCommand;
+    /// assert!(DriveCommand::forward_const(1).is_moving());
+    /// assert!(!DriveCommand::FORWARD_STOPPED.is_moving());
+    /// assert!(!DriveCommand::BACKWARD_STOPPED.is_moving());
+    /// assert!(!DriveCommand::Brake.is_moving());
+    /// ```
+    pub const fn is_moving(&self) -> bool {
+        matches!(self, DriveCommand::Forward(s) | DriveCommand::Backward(s) if s.get() > 0)
+    }
+
+    /// Returns whether this is a [`DriveCommand::Brake`] command.
+    pub const fn is_braking(&self) -> bool {
+        matches!(self, DriveCommand::Brake)
+    }
+
+    /// Returns whether this command stops the motor without actively braking, i.e. it is either
+    /// [`DriveCommand::Stop`] or drives forward or backward with a speed of 0.
+    ///
+    /// Use [`DriveCommand::is_coasting`] if you only want to know whether this is a [`DriveCommand::Stop`] command.
+    pub const fn is_stopped(&self) -> bool {
+        matches!(
+            self,
+            DriveCommand::Stop
+                | DriveCommand::Forward(Percent(0))
+                | DriveCommand::Backward(Percent(0))
+        )
+    }
+
+    /// Returns whether this is a [`DriveCommand::Stop`] command, i.e. lets the motor coast.
+    pub const fn is_coasting(&self) -> bool {
+        matches!(self, DriveCommand::Stop)
+    }
+
+    /// Returns the logical states of the IN1 & IN2 pins (`true` meaning high) for this command as defined by the
+    /// truth table in the datasheet, without applying them.
+    ///
+    /// | Command    | IN1   | IN2   |
+    /// |------------|-------|-------|
+    /// | `Forward`  | high  | low   |
+    /// | `Backward` | low   | high  |
+    /// | `Brake`    | high  | high  |
+    /// | `Stop`     | low   | low   |
+    ///
+    /// Note that this does not take [`Motor::invert_direction`] into account.
+    pub const fn to_pin_states(&self) -> (bool, bool) {
+        match self {
+            DriveCommand::Forward(_) => (true, false),
+            DriveCommand::Backward(_) => (false, true),
+            DriveCommand::Brake => (true, true),
+            DriveCommand::Stop => (false, false),
+        }
+    }
+}
+
+impl core::ops::Neg for DriveCommand {
+    type Output = DriveCommand;
+
+    /// See [`DriveCommand::reverse`].
+    fn neg(self) -> Self::Output {
+        self.reverse()
+    }
+}
+
+impl TryFrom<i8> for DriveCommand {
+    type Error = InvalidSpeedError;
+
+    /// See [`DriveCommand::from_signed_speed`].
+    fn try_from(speed: i8) -> Result<Self, Self::Error> {
+        DriveCommand::from_signed_speed(speed)
+    }
+}
+
+impl TryFrom<u8> for DriveCommand {
+    type Error = InvalidSpeedError;
+
+    /// Create a [`DriveCommand::Forward`] with the defined speed (in percentage).
+    ///
+    /// # Errors
+    /// The specified speed must be between 0 and 100 (inclusive), otherwise you will get an [`InvalidSpeedError`].
+    fn try_from(speed: u8) -> Result<Self, Self::Error> {
+        Percent::try_from(speed).map(DriveCommand::Forward)
+    }
+}
+
+impl core::fmt::Display for DriveCommand {
+    /// Formats the command including its speed, e.g. `Forward(75%)`, `Brake` or `Stop`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        use DriveCommand::*;
+        match self {
+            Forward(s) => write!(f, "Forward({}%)", s.get()),
+            Backward(s) => write!(f, "Backward({}%)", s.get()),
+            Brake => write!(f, "Brake"),
+            Stop => write!(f, "Stop"),
+        }
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDisplay for DriveCommand {
+    /// Formats the command like its [`Display`](core::fmt::Display) implementation, e.g. `Forward(75%)`.
+    fn fmt<W: ufmt::uWrite + ?Sized>(
+        &self,
+        f: &mut ufmt::Formatter<'_, W>,
+    ) -> Result<(), W::Error> {
+        use DriveCommand::*;
+        match self {
+            Forward(s) => ufmt::uwrite!(f, "Forward({}%)", s.get()),
+            Backward(s) => ufmt::uwrite!(f, "Backward({}%)", s.get()),
+            Brake => f.write_str("Brake"),
+            Stop => f.write_str("Stop"),
+        }
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDebug for Percent {
+    fn fmt<W: ufmt::uWrite + ?Sized>(
+        &self,
+        f: &mut ufmt::Formatter<'_, W>,
+    ) -> Result<(), W::Error> {
+        f.debug_tuple("Percent")?.field(&self.0)?.finish()
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDebug for DriveCommand {
+    fn fmt<W: ufmt::uWrite + ?Sized>(
+        &self,
+        f: &mut ufmt::Formatter<'_, W>,
+    ) -> Result<(), W::Error> {
+        use DriveCommand::*;
+        match self {
+            Forward(s) => f.debug_tuple("Forward")?.field(s)?.finish(),
+            Backward(s) => f.debug_tuple("Backward")?.field(s)?.finish(),
+            Brake => f.write_str("Brake"),
+            Stop => f.write_str("Stop"),
+        }
+    }
+}
+
+impl From<(Direction, Percent)> for DriveCommand {
+    /// Create a [`DriveCommand::Forward`] or [`DriveCommand::Backward`] command with the defined speed.
+    fn from((direction, speed): (Direction, Percent)) -> Self {
+        match direction {
+            Direction::Forward => DriveCommand::forward(speed),
+            Direction::Backward => DriveCommand::backward(speed),
+        }
+    }
+}
+
+impl From<DriveCommand> for i8 {
+    /// See [`DriveCommand::signed_speed`].
+    fn from(drive_command: DriveCommand) -> Self {
+        drive_command.signed_speed()
+    }
+}
+
+/// Defines the direction in which a motor can be driven.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Direction {
+    /// Drive forward
+    Forward,
+    /// Drive backward
+    Backward,
+}
+
+/// Defines the gains of the proportional-integral controller used by [`Motor::update_closed_loop`].
+///
+/// The gains map an error in RPM to a speed in percentage, e.g. with `kp = 0.5` an error of 10 RPM results in
+/// a speed of 5%. With the default gains (both 0) the closed loop mode stops the motor.
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+pub struct ClosedLoopGains {
+    /// The proportional gain.
+    pub kp: f32,
+    /// The integral gain.
+    pub ki: f32,
+}
+
+/// A source of speed measurements for the closed loop mode of a [`Motor`], e.g. a quadrature encoder attached to its
+/// shaft. See [`Motor::update_closed_loop_from`].
+///
+/// # Usage example
+/// ```
+/// # use tb6612fng::SpeedFeedback;
+/// struct Encoder {
+///     ticks_per_interval: i32,
+/// }
+///
+/// impl SpeedFeedback for Encoder {
+///     type Error = core::convert::Infallible;
+///
+///     fn measured_rpm(&mut self) -> Result<i32, Self::Error> {
+///         // e.g. 12 ticks per revolution, sampled every 100ms
+///         Ok(self.ticks_per_interval * 10 * 60 / 12)
+///     }
+/// }
+/// ```
+pub trait SpeedFeedback {
+    /// The error which can happen when measuring the speed.
+    type Error;
+
+    /// Returns the currently measured speed in RPM. Negative values mean that the motor is turning backward.
+    ///
+    /// # Errors
+    /// This is specific to the implementation, e.g. a failed bus transaction to an encoder interface.
+    fn measured_rpm(&mut self) -> Result<i32, Self::Error>;
+}
+
+/// Defines errors which can happen when calling [`Motor::update_closed_loop_from()`].
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ClosedLoopError<FeedbackError, MotorError> {
+    /// An error in measuring the speed. The motor has not been driven.
+    Feedback(FeedbackError),
+    /// An error in driving the motor
+    Motor(MotorError),
+}
+
+impl<FeedbackError: Debug, MotorError: Debug> core::fmt::Display
+    for ClosedLoopError<FeedbackError, MotorError>
 {
-    /// Instantiate a new [`Motor`] with the defined pins.
-    /// This also automatically enables the PWM pin.
-    /// The initial state of the motor will be set to [stopped](DriveCommand::Stop).
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        use ClosedLoopError::*;
+        match self {
+            Feedback(_) => write!(f, "failed to measure the speed"),
+            Motor(_) => write!(f, "failed to drive the motor"),
+        }
+    }
+}
+
+impl<FeedbackError: Debug + Error + 'static, MotorError: Debug + Error + 'static> Error
+    for ClosedLoopError<FeedbackError, MotorError>
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use ClosedLoopError::*;
+        match self {
+            Feedback(e) => Some(e),
+            Motor(e) => Some(e),
+        }
+    }
+}
+
+/// The state of a [`Motor`] which can be captured and restored, e.g. to re-apply the last known state after a
+/// fault. See [`Motor::capture_state`] and [`Motor::restore_state`].
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MotorState {
+    /// The drive command of the motor.
+    pub drive_command: DriveCommand,
+}
+
+/// A snapshot of the state of a [`Tb6612fng`] controller, see [`Tb6612fng::snapshot`].
+///
+/// If the `serde` feature is enabled this can be serialized, e.g. to send it as part of a telemetry packet.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControllerSnapshot {
+    /// The current drive command of motor A.
+    pub motor_a: DriveCommand,
+    /// The current drive command of motor B.
+    pub motor_b: DriveCommand,
+    /// Whether the standby mode is enabled.
+    pub standby_enabled: bool,
+}
+
+/// Represents a TB6612FNG controller.
+///
+/// Use the [`Motor`] struct directly if you only have one motor.
+/// See the crate-level comment for further details on when to use what.
+#[derive(Debug)]
+pub struct Tb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY> {
+    /// The first motor, labelled as 'A' on the chip
+    pub motor_a: Motor<MAIN1, MAIN2, MAPWM>,
+    /// The second motor, labelled as 'B' on the chip
+    pub motor_b: Motor<MBIN1, MBIN2, MBPWM>,
+    /// The standby pin used to put both motors on standby
+    standby: STBY,
+    /// Whether standby has last been enabled or disabled
+    standby_enabled: bool,
+}
+
+impl<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>
+    Tb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>
+where
+    MAIN1: OutputPin,
+    MAIN2: OutputPin,
+    MAPWM: SetDutyCycle,
+    MBIN1: OutputPin,
+    MBIN2: OutputPin,
+    MBPWM: SetDutyCycle,
+    STBY: OutputPin,
+{
+    /// Instantiate a new [`Tb6612fng`] with the defined pins.
+    /// This also automatically enables the two PWM pins.
+    /// The initial state of the motors will be set to [stopped](DriveCommand::Stop).
+    /// The initial state of standby will be *disabled*.
+    ///
+    /// Use [`Tb6612fng::new_in_standby`] instead if your system must power up in a safe state, i.e. with the motors
+    /// staying quiescent until you explicitly call [`Tb6612fng::disable_standby`].
     ///
     /// # Errors
     /// If any of the underlying pin interactions fail these errors will be propagated up.
@@ -268,277 +1179,7413 @@ where
     ///
     /// # Usage example
     /// ```
+    ///
+    /// # fn main() -> Result<(), Box<dyn core::error::Error>> {
     /// # use embedded_hal_mock::eh1::digital::Mock as PinMock;
     /// # use embedded_hal_mock::eh1::pwm::Mock as PwmMock;
     /// # use embedded_hal_mock::eh1::pwm::Transaction as PwmTransaction;
     /// # use embedded_hal_mock::eh1::digital::Transaction as PinTransaction;
-    /// # use embedded_hal_mock::eh1::digital::State::{Low};
-    /// # let motor_in1 = PinMock::new(&[PinTransaction::set(Low)]);
-    /// # let mut motor_in1_ = motor_in1.clone();
-    /// # let motor_in2 = PinMock::new(&[PinTransaction::set(Low)]);
-    /// # let mut motor_in2_ = motor_in2.clone();
-    /// # let motor_pwm = PwmMock::new(&[PwmTransaction::max_duty_cycle(100), PwmTransaction::set_duty_cycle(0)]);
-    /// # let mut motor_pwm_ = motor_pwm.clone();
-    /// use tb6612fng::Motor;
+    /// # use embedded_hal_mock::eh1::digital::State::{High, Low};
     ///
-    /// let motor = Motor::new(
-    ///     motor_in1,
-    ///     motor_in2,
-    ///     motor_pwm,
+    /// # let motor_a_in1 = PinMock::new(&[PinTransaction::set(Low)]);
+    /// # let mut motor_a_in1_ = motor_a_in1.clone();
+    /// # let motor_a_in2 = PinMock::new(&[PinTransaction::set(Low)]);
+    /// # let mut motor_a_in2_ = motor_a_in2.clone();
+    /// # let motor_a_pwm = PwmMock::new(&[PwmTransaction::max_duty_cycle(100), PwmTransaction::set_duty_cycle(0)]);
+    /// # let mut motor_a_pwm_ = motor_a_pwm.clone();
+    ///
+    /// # let motor_b_in1 = PinMock::new(&[PinTransaction::set(Low)]);
+    /// # let mut motor_b_in1_ = motor_b_in1.clone();
+    /// # let motor_b_in2 = PinMock::new(&[PinTransaction::set(Low)]);
+    /// # let mut motor_b_in2_ = motor_b_in2.clone();
+    /// # let motor_b_pwm = PwmMock::new(&[PwmTransaction::max_duty_cycle(100), PwmTransaction::set_duty_cycle(0)]);
+    /// # let mut motor_b_pwm_ = motor_b_pwm.clone();
+    ///
+    /// # let standby = PinMock::new(&[PinTransaction::set(High)]);
+    /// # let mut standby_ = standby.clone();
+    ///
+    /// use tb6612fng::{Motor, Tb6612fng};
+    /// let controller = Tb6612fng::new(
+    ///     Motor::new(motor_a_in1, motor_a_in2, motor_a_pwm)?,
+    ///     Motor::new(motor_b_in1, motor_b_in2, motor_b_pwm)?,
+    ///     standby,
     /// );
     ///
-    /// # motor_in1_.done();
-    /// # motor_in2_.done();
-    /// # motor_pwm_.done();
+    /// # motor_a_in1_.done();
+    /// # motor_a_in2_.done();
+    /// # motor_a_pwm_.done();
+    /// # motor_b_in1_.done();
+    /// # motor_b_in2_.done();
+    /// # motor_b_pwm_.done();
+    /// # standby_.done();
+    /// # Ok(())
+    /// # }
     /// ```
     #[allow(clippy::type_complexity)]
     pub fn new(
-        in1: IN1,
-        in2: IN2,
-        pwm: PWM,
-    ) -> Result<Motor<IN1, IN2, PWM>, MotorError<IN1::Error, IN2::Error, PWM::Error>> {
-        let mut motor = Motor {
-            in1,
-            in2,
-            pwm,
-            current_drive_command: DriveCommand::Stop,
+        motor_a: Motor<MAIN1, MAIN2, MAPWM>,
+        motor_b: Motor<MBIN1, MBIN2, MBPWM>,
+        standby: STBY,
+    ) -> Result<
+        Tb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>,
+        Tb6612fngError<STBY::Error>,
+    > {
+        let mut controller = Tb6612fng {
+            motor_a,
+            motor_b,
+            standby,
+            standby_enabled: false,
+        };
+
+        controller
+            .disable_standby()
+            .map_err(Tb6612fngError::Standby)?;
+
+        Ok(controller)
+    }
+
+    /// Instantiate a new [`Tb6612fng`] with the defined pins like [`Tb6612fng::new`], but with standby *enabled*,
+    /// e.g. for designs where the motors should stay powered down until they are explicitly enabled via
+    /// [`Tb6612fng::disable_standby`].
+    ///
+    /// The motors are not driven again, i.e. they keep the commands they have been created with (which is
+    /// [stopped](DriveCommand::Stop) when using [`Motor::new`]) and these are applied as soon as standby is disabled.
+    ///
+    /// # Errors
+    /// If the underlying pin interaction fails this error will be propagated up.
+    /// The error is specific to your HAL.
+    #[allow(clippy::type_complexity)]
+    pub fn new_in_standby(
+        motor_a: Motor<MAIN1, MAIN2, MAPWM>,
+        motor_b: Motor<MBIN1, MBIN2, MBPWM>,
+        standby: STBY,
+    ) -> Result<
+        Tb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>,
+        Tb6612fngError<STBY::Error>,
+    > {
+        let mut controller = Tb6612fng {
+            motor_a,
+            motor_b,
+            standby,
+            standby_enabled: false,
         };
 
-        motor.drive(motor.current_drive_command)?;
+        controller
+            .enable_standby()
+            .map_err(Tb6612fngError::Standby)?;
+
+        Ok(controller)
+    }
+
+    /// Re-assemble a controller from its parts, e.g. as returned by [`Tb6612fng::release`].
+    ///
+    /// Unlike [`Tb6612fng::new`] this does not interact with any pin, i.e. the standby pin is left as-is. Thus
+    /// `standby_enabled` must reflect the current state of the standby pin, otherwise
+    /// [`Tb6612fng::standby_enabled`] will report the wrong state.
+    pub fn from_parts(
+        motor_a: Motor<MAIN1, MAIN2, MAPWM>,
+        motor_b: Motor<MBIN1, MBIN2, MBPWM>,
+        standby: STBY,
+        standby_enabled: bool,
+    ) -> Tb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY> {
+        Tb6612fng {
+            motor_a,
+            motor_b,
+            standby,
+            standby_enabled,
+        }
+    }
+
+    /// Drive both motors with the defined commands, first motor A and then motor B.
+    ///
+    /// # Errors
+    /// If driving either motor fails the error will be propagated up, telling you which motor failed.
+    /// See [`Motor::drive`] for the possible errors.
+    ///
+    /// If driving motor A fails then motor B will not be driven anymore, i.e. it keeps its previous command.
+    #[allow(clippy::type_complexity)]
+    pub fn drive(
+        &mut self,
+        drive_command_a: DriveCommand,
+        drive_command_b: DriveCommand,
+    ) -> Result<
+        (),
+        Tb6612fngDriveError<
+            MotorError<MAIN1::Error, MAIN2::Error, MAPWM::Error>,
+            MotorError<MBIN1::Error, MBIN2::Error, MBPWM::Error>,
+        >,
+    > {
+        self.motor_a
+            .drive(drive_command_a)
+            .map_err(Tb6612fngDriveError::MotorA)?;
+        self.motor_b
+            .drive(drive_command_b)
+            .map_err(Tb6612fngDriveError::MotorB)?;
+
+        Ok(())
+    }
+
+    /// Drive both motors of a differential drive (i.e. a two-wheeled robot) with the defined linear and angular speeds.
+    ///
+    /// Motor A is treated as the left and motor B as the right motor. The `linear` speed (in percentage) defines how
+    /// fast the robot drives forward (positive values) or backward (negative values) and the `angular` speed defines
+    /// how fast it turns counterclockwise, i.e. to the left (positive values) or clockwise, i.e. to the right
+    /// (negative values), following the usual convention of differential drive kinematics. They are mixed as
+    /// `left = linear - angular` and `right = linear + angular` and then clamped to -100..=100. A resulting speed of 0
+    /// [stops](DriveCommand::Stop) the motor.
+    ///
+    /// This is the same as [`Tb6612fng::drive_arcade`] with `steering = -angular`, except that invalid inputs are
+    /// rejected instead of being clamped.
+    ///
+    /// # Errors
+    /// Both `linear` and `angular` must be between -100 and 100 (inclusive), otherwise you will get a
+    /// [`Tb6612fngDriveError::InvalidSpeed`] error. In this case no motor is driven.
+    ///
+    /// See [`Tb6612fng::drive`] for the other possible errors.
+    #[allow(clippy::type_complexity)]
+    pub fn differential_drive(
+        &mut self,
+        linear: i8,
+        angular: i8,
+    ) -> Result<
+        (),
+        Tb6612fngDriveError<
+            MotorError<MAIN1::Error, MAIN2::Error, MAPWM::Error>,
+            MotorError<MBIN1::Error, MBIN2::Error, MBPWM::Error>,
+        >,
+    > {
+        if !(-100..=100).contains(&linear) || !(-100..=100).contains(&angular) {
+            return Err(Tb6612fngDriveError::InvalidSpeed);
+        }
+
+        // a counterclockwise turn is a turn to the left, i.e. negative steering
+        let (left, right) = mix_arcade(linear, -angular);
+        self.drive_signed_speeds(left, right)
+    }
+
+    /// Drive both motors with the same command, e.g. to drive straight or (with one motor mounted the other way
+    /// around) to spin in place.
+    ///
+    /// This is a shorthand for calling [`Tb6612fng::drive`] with the same command for both motors.
+    ///
+    /// # Errors
+    /// See [`Tb6612fng::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn drive_both(
+        &mut self,
+        drive_command: DriveCommand,
+    ) -> Result<
+        (),
+        Tb6612fngDriveError<
+            MotorError<MAIN1::Error, MAIN2::Error, MAPWM::Error>,
+            MotorError<MBIN1::Error, MBIN2::Error, MBPWM::Error>,
+        >,
+    > {
+        self.drive(drive_command, drive_command)
+    }
+
+    /// Actively brake both motors.
+    ///
+    /// This is a shorthand for calling [`Tb6612fng::drive`] with [`DriveCommand::Brake`] for both motors.
+    ///
+    /// # Errors
+    /// See [`Tb6612fng::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn brake_all(
+        &mut self,
+    ) -> Result<
+        (),
+        Tb6612fngDriveError<
+            MotorError<MAIN1::Error, MAIN2::Error, MAPWM::Error>,
+            MotorError<MBIN1::Error, MBIN2::Error, MBPWM::Error>,
+        >,
+    > {
+        self.drive(DriveCommand::Brake, DriveCommand::Brake)
+    }
+
+    /// Let both motors coast, i.e. stop them without actively braking.
+    ///
+    /// This is a shorthand for calling [`Tb6612fng::drive`] with [`DriveCommand::Stop`] for both motors.
+    ///
+    /// # Errors
+    /// See [`Tb6612fng::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn stop_all(
+        &mut self,
+    ) -> Result<
+        (),
+        Tb6612fngDriveError<
+            MotorError<MAIN1::Error, MAIN2::Error, MAPWM::Error>,
+            MotorError<MBIN1::Error, MBIN2::Error, MBPWM::Error>,
+        >,
+    > {
+        self.drive(DriveCommand::Stop, DriveCommand::Stop)
+    }
+
+    /// Bring everything to a halt: actively brake both motors and then enable standby.
+    ///
+    /// This is also available via the [`EmergencyStop`] trait.
+    ///
+    /// The motors are braked first so that they are actively held momentarily before the driver lets them float.
+    /// All steps are attempted even if an earlier one fails, as this is meant to be used in fault handlers.
+    ///
+    /// # Errors
+    /// If any of the underlying pin interactions fail the first error will be propagated up, telling you which part
+    /// failed. See [`Motor::drive`] for the possible motor errors.
+    #[allow(clippy::type_complexity)]
+    pub fn emergency_stop(
+        &mut self,
+    ) -> Result<
+        (),
+        Tb6612fngControlError<
+            MotorError<MAIN1::Error, MAIN2::Error, MAPWM::Error>,
+            MotorError<MBIN1::Error, MBIN2::Error, MBPWM::Error>,
+            STBY::Error,
+        >,
+    > {
+        let motor_a = self.motor_a.drive(DriveCommand::Brake);
+        let motor_b = self.motor_b.drive(DriveCommand::Brake);
+        let standby = self.enable_standby();
+
+        motor_a.map_err(Tb6612fngControlError::MotorA)?;
+        motor_b.map_err(Tb6612fngControlError::MotorB)?;
+        standby.map_err(Tb6612fngControlError::Standby)
+    }
+
+    /// Drive motor A with the defined command, but only if the controller is not in standby.
+    ///
+    /// Unlike driving [`Tb6612fng::motor_a`](field@Tb6612fng::motor_a) directly this makes it explicit that a command
+    /// given while in standby would have no physical effect (until [`Tb6612fng::disable_standby`] is called), which
+    /// helps catching logic errors where disabling standby has been forgotten.
+    ///
+    /// # Errors
+    /// Returns [`Tb6612fngDriveError::InStandby`] (without changing the command of the motor) if the
+    /// [standby is enabled](Tb6612fng::standby_enabled). Otherwise errors of [`Motor::drive`] are returned as
+    /// [`Tb6612fngDriveError::MotorA`].
+    #[allow(clippy::type_complexity)]
+    pub fn drive_motor_a(
+        &mut self,
+        drive_command: DriveCommand,
+    ) -> Result<
+        (),
+        Tb6612fngDriveError<
+            MotorError<MAIN1::Error, MAIN2::Error, MAPWM::Error>,
+            MotorError<MBIN1::Error, MBIN2::Error, MBPWM::Error>,
+        >,
+    > {
+        if self.standby_enabled {
+            return Err(Tb6612fngDriveError::InStandby);
+        }
+
+        self.motor_a
+            .drive(drive_command)
+            .map_err(Tb6612fngDriveError::MotorA)
+    }
+
+    /// Drive motor B with the defined command, but only if the controller is not in standby.
+    ///
+    /// See [`Tb6612fng::drive_motor_a`] for details.
+    ///
+    /// # Errors
+    /// Returns [`Tb6612fngDriveError::InStandby`] (without changing the command of the motor) if the
+    /// [standby is enabled](Tb6612fng::standby_enabled). Otherwise errors of [`Motor::drive`] are returned as
+    /// [`Tb6612fngDriveError::MotorB`].
+    #[allow(clippy::type_complexity)]
+    pub fn drive_motor_b(
+        &mut self,
+        drive_command: DriveCommand,
+    ) -> Result<
+        (),
+        Tb6612fngDriveError<
+            MotorError<MAIN1::Error, MAIN2::Error, MAPWM::Error>,
+            MotorError<MBIN1::Error, MBIN2::Error, MBPWM::Error>,
+        >,
+    > {
+        if self.standby_enabled {
+            return Err(Tb6612fngDriveError::InStandby);
+        }
+
+        self.motor_b
+            .drive(drive_command)
+            .map_err(Tb6612fngDriveError::MotorB)
+    }
+
+    /// Drive both motors with the defined commands like [`Tb6612fng::drive`], but drive motor B even if driving
+    /// motor A failed. This way a single failing pin doesn't stop both motors.
+    ///
+    /// The returned [`Tb6612fngDriveResults`] contains the result of each motor. As partial success is possible you
+    /// should check both (the motor which failed keeps its previous command, see [`Motor::drive`]).
+    #[allow(clippy::type_complexity)]
+    #[must_use]
+    pub fn try_drive_both(
+        &mut self,
+        drive_command_a: DriveCommand,
+        drive_command_b: DriveCommand,
+    ) -> Tb6612fngDriveResults<
+        MotorError<MAIN1::Error, MAIN2::Error, MAPWM::Error>,
+        MotorError<MBIN1::Error, MBIN2::Error, MBPWM::Error>,
+    > {
+        Tb6612fngDriveResults {
+            motor_a: self.motor_a.drive(drive_command_a),
+            motor_b: self.motor_b.drive(drive_command_b),
+        }
+    }
+
+    /// Drive both motors of a differential drive (i.e. a two-wheeled robot) with arcade-style controls.
+    ///
+    /// Motor A is treated as the left and motor B as the right motor. The `throttle` defines the speed (in percentage)
+    /// at which the robot drives forward (positive values) or backward (negative values) and the `steering` defines
+    /// how much it turns to the right, i.e. clockwise (positive values) or to the left, i.e. counterclockwise
+    /// (negative values). Both are clamped to -100..=100. They are mixed as `left = throttle + steering` and
+    /// `right = throttle - steering`, again clamped to -100..=100, i.e. a `throttle` of 0 spins the robot in place.
+    /// A resulting speed of 0 [stops](DriveCommand::Stop) the motor.
+    ///
+    /// This convention is used by all arcade-style helpers of this crate (e.g. [`DifferentialDrive::drive_arcade`]).
+    /// [`Tb6612fng::differential_drive`] mixes its speeds the same way, but its `angular` speed follows the kinematics
+    /// convention and thus has the opposite sign of the `steering`.
+    ///
+    /// # Errors
+    /// See [`Tb6612fng::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn drive_arcade(
+        &mut self,
+        throttle: i8,
+        steering: i8,
+    ) -> Result<
+        (),
+        Tb6612fngDriveError<
+            MotorError<MAIN1::Error, MAIN2::Error, MAPWM::Error>,
+            MotorError<MBIN1::Error, MBIN2::Error, MBPWM::Error>,
+        >,
+    > {
+        let (left, right) = mix_arcade(throttle, steering);
+        self.drive_signed_speeds(left, right)
+    }
+
+    /// Drive motor A and B with the defined signed speeds, clamped to -100..=100.
+    #[allow(clippy::type_complexity)]
+    fn drive_signed_speeds(
+        &mut self,
+        speed_a: i16,
+        speed_b: i16,
+    ) -> Result<
+        (),
+        Tb6612fngDriveError<
+            MotorError<MAIN1::Error, MAIN2::Error, MAPWM::Error>,
+            MotorError<MBIN1::Error, MBIN2::Error, MBPWM::Error>,
+        >,
+    > {
+        self.drive(
+            DriveCommand::from_signed_speed_clamped(speed_a),
+            DriveCommand::from_signed_speed_clamped(speed_b),
+        )
+    }
+
+    /// Enable standby. This ignores any other setting currently done on the motors and puts them into standby.
+    ///
+    /// Note that this does not change any commands on the motors, i.e. the PWM signal will continue
+    /// and once [`Tb6612fng::disable_standby`] is called the motor will pick up where it left off (unless the command was changed in-between).
+    ///
+    /// # Errors
+    /// If the underlying pin interaction fails this error will be propagated up.
+    /// The error is specific to your HAL.
+    pub fn enable_standby(&mut self) -> Result<(), STBY::Error> {
+        log_debug!(
+            "enabling standby (motor A: {}, motor B: {})",
+            self.motor_a.current_drive_command,
+            self.motor_b.current_drive_command
+        );
+        self.standby.set_low()?;
+        self.standby_enabled = true;
+        Ok(())
+    }
+
+    /// Disable standby. Note that the last active commands on the motors will resume.
+    ///
+    /// # Errors
+    /// If the underlying pin interaction fails this error will be propagated up.
+    /// The error is specific to your HAL.
+    pub fn disable_standby(&mut self) -> Result<(), STBY::Error> {
+        log_debug!(
+            "disabling standby, resuming motor A: {}, motor B: {}",
+            self.motor_a.current_drive_command,
+            self.motor_b.current_drive_command
+        );
+        self.standby.set_high()?;
+        self.standby_enabled = false;
+        Ok(())
+    }
+
+    /// Enable standby for the lifetime of the returned [`StandbyGuard`], e.g. while reconfiguring the motors.
+    ///
+    /// When the guard is dropped the standby mode is restored to its previous state, i.e. it is only disabled again if
+    /// it was disabled before calling this method. The controller can be accessed through the guard in the meantime.
+    ///
+    /// # Errors
+    /// If the underlying pin interaction fails this error will be propagated up.
+    /// The error is specific to your HAL.
+    #[allow(clippy::type_complexity)]
+    pub fn standby_scope(
+        &mut self,
+    ) -> Result<StandbyGuard<'_, MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>, STBY::Error> {
+        let standby_was_enabled = self.standby_enabled;
+        self.enable_standby()?;
+
+        Ok(StandbyGuard {
+            controller: self,
+            standby_was_enabled,
+        })
+    }
+
+    /// Toggle the standby mode, i.e. enable it if it is currently disabled and vice versa.
+    ///
+    /// The current state is determined by querying the [set state](StatefulOutputPin::is_set_high) of the standby pin.
+    ///
+    /// # Errors
+    /// If the underlying pin interaction fails this error will be propagated up.
+    /// The error is specific to your HAL.
+    pub fn toggle_standby(&mut self) -> Result<(), STBY::Error>
+    where
+        STBY: StatefulOutputPin,
+    {
+        // the standby pin is active low
+        if self.standby.is_set_high()? {
+            self.enable_standby()
+        } else {
+            self.disable_standby()
+        }
+    }
+
+    /// Pulse the standby pin, i.e. enable the standby mode and immediately disable it again, e.g. to reset the
+    /// internal state of the TB6612FNG. Afterwards the standby mode is disabled.
+    ///
+    /// This works with any [`OutputPin`], see [`Tb6612fng::toggle_standby`] if you want to flip the current state.
+    ///
+    /// # Errors
+    /// If the underlying pin interaction fails this error will be propagated up.
+    /// The error is specific to your HAL.
+    #[doc(alias = "toggle_standby_uncached")]
+    pub fn pulse_standby(&mut self) -> Result<(), STBY::Error> {
+        self.enable_standby()?;
+        self.disable_standby()
+    }
+
+    /// Returns whether the standby mode is enabled.
+    ///
+    /// Unlike [`Tb6612fng::current_standby`] this does not query the pin but returns the state which has last been
+    /// set successfully via [`Tb6612fng::enable_standby`] or [`Tb6612fng::disable_standby`], thus it works with any
+    /// [`OutputPin`].
+    pub fn standby_enabled(&self) -> bool {
+        self.standby_enabled
+    }
+
+    /// Returns a reference to the standby pin, e.g. to call HAL-specific methods on it.
+    pub fn standby_pin_ref(&self) -> &STBY {
+        &self.standby
+    }
+
+    /// Returns a mutable reference to the standby pin, e.g. to call HAL-specific methods on it.
+    ///
+    /// This bypasses [`Tb6612fng::enable_standby`] and [`Tb6612fng::disable_standby`]: if you change the output of the
+    /// pin through this reference then [`Tb6612fng::standby_enabled`] will report a stale state until standby is
+    /// enabled or disabled through the controller again.
+    pub fn standby_pin_mut(&mut self) -> &mut STBY {
+        &mut self.standby
+    }
+
+    /// Returns a mutable reference to motor A, but only if standby is disabled (`None` otherwise), i.e. if driving it
+    /// has a physical effect.
+    ///
+    /// This is an alternative to accessing the [`motor_a`](field@Tb6612fng::motor_a) field directly which makes sure
+    /// that no commands are given to the motor while in standby, like [`Tb6612fng::drive_motor_a`].
+    pub fn motor_a(&mut self) -> Option<&mut Motor<MAIN1, MAIN2, MAPWM>> {
+        (!self.standby_enabled).then_some(&mut self.motor_a)
+    }
+
+    /// Returns a mutable reference to motor B, but only if standby is disabled (`None` otherwise), see
+    /// [`Tb6612fng::motor_a()`].
+    pub fn motor_b(&mut self) -> Option<&mut Motor<MBIN1, MBIN2, MBPWM>> {
+        (!self.standby_enabled).then_some(&mut self.motor_b)
+    }
+
+    /// Returns mutable references to both motors at the same time, e.g. to pass them to a function expecting
+    /// `(&mut Motor<...>, &mut Motor<...>)`.
+    ///
+    /// This is equivalent to borrowing the [`motor_a`](field@Tb6612fng::motor_a) and
+    /// [`motor_b`](field@Tb6612fng::motor_b) fields individually.
+    pub fn motors_mut(
+        &mut self,
+    ) -> (
+        &mut Motor<MAIN1, MAIN2, MAPWM>,
+        &mut Motor<MBIN1, MBIN2, MBPWM>,
+    ) {
+        (&mut self.motor_a, &mut self.motor_b)
+    }
+
+    /// Returns a snapshot of the current state of the controller, i.e. the current drive commands of both motors and
+    /// whether the standby mode is enabled.
+    pub fn snapshot(&self) -> ControllerSnapshot {
+        ControllerSnapshot {
+            motor_a: self.motor_a.current_drive_command,
+            motor_b: self.motor_b.current_drive_command,
+            standby_enabled: self.standby_enabled,
+        }
+    }
+
+    /// Returns whether the standby mode is enabled.
+    ///
+    /// *NOTE* this does *not* read the electrical state of the pin, see [`StatefulOutputPin`]
+    ///
+    /// # Errors
+    /// If the underlying pin interaction fails this error will be propagated up.
+    /// The error is specific to your HAL.
+    pub fn current_standby(&mut self) -> Result<bool, STBY::Error>
+    where
+        STBY: StatefulOutputPin,
+    {
+        // the standby pin is active low
+        self.standby.is_set_low()
+    }
+
+    /// Returns whether the standby pin is high as reported by the pin driver, i.e. `true` means that the standby mode
+    /// is *disabled* (the standby pin is active low).
+    ///
+    /// Unlike [`Tb6612fng::standby_enabled`] this queries the pin instead of the state cached by the controller and
+    /// unlike [`Tb6612fng::current_standby`] it reports the raw pin level rather than the standby mode. This is meant
+    /// for hardware debugging, e.g. to diagnose cases where the pin isn't behaving as commanded.
+    ///
+    /// # Errors
+    /// If the underlying pin interaction fails this error will be propagated up.
+    /// The error is specific to your HAL.
+    pub fn electrical_standby_state(&mut self) -> Result<bool, STBY::Error>
+    where
+        STBY: StatefulOutputPin,
+    {
+        self.standby.is_set_high()
+    }
+
+    /// Release the controller, returning the two motors and the standby pin so that they can be used for something else.
+    ///
+    /// Note that this does not change the output of any pin, i.e. the standby pin remains in its current state.
+    ///
+    /// Use [`Tb6612fng::from_parts`] to re-assemble the controller.
+    #[doc(alias = "into_parts")]
+    pub fn release(self) -> (Motor<MAIN1, MAIN2, MAPWM>, Motor<MBIN1, MBIN2, MBPWM>, STBY) {
+        (self.motor_a, self.motor_b, self.standby)
+    }
+}
+
+/// Keeps a [`Tb6612fng`] in standby until it is dropped, see [`Tb6612fng::standby_scope`].
+///
+/// The controller can be accessed through the guard (it implements [`Deref`](core::ops::Deref) and
+/// [`DerefMut`](core::ops::DerefMut)).
+///
+/// As `drop` cannot return errors, a failure to restore the standby mode is ignored (and logged if the `defmt` or
+/// `log` feature is enabled). Use [`StandbyGuard::release`] if you need to handle it.
+#[derive(Debug)]
+pub struct StandbyGuard<'a, MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>
+where
+    MAIN1: OutputPin,
+    MAIN2: OutputPin,
+    MAPWM: SetDutyCycle,
+    MBIN1: OutputPin,
+    MBIN2: OutputPin,
+    MBPWM: SetDutyCycle,
+    STBY: OutputPin,
+{
+    controller: &'a mut Tb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>,
+    standby_was_enabled: bool,
+}
+
+impl<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>
+    StandbyGuard<'_, MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>
+where
+    MAIN1: OutputPin,
+    MAIN2: OutputPin,
+    MAPWM: SetDutyCycle,
+    MBIN1: OutputPin,
+    MBIN2: OutputPin,
+    MBPWM: SetDutyCycle,
+    STBY: OutputPin,
+{
+    /// Release the guard, restoring the standby mode to its previous state.
+    ///
+    /// # Errors
+    /// If the underlying pin interaction fails this error will be propagated up.
+    /// The error is specific to your HAL.
+    pub fn release(self) -> Result<(), STBY::Error> {
+        let mut guard = core::mem::ManuallyDrop::new(self);
+        guard.restore()
+    }
+
+    fn restore(&mut self) -> Result<(), STBY::Error> {
+        if self.standby_was_enabled {
+            self.controller.enable_standby()
+        } else {
+            self.controller.disable_standby()
+        }
+    }
+}
+
+impl<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY> core::ops::Deref
+    for StandbyGuard<'_, MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>
+where
+    MAIN1: OutputPin,
+    MAIN2: OutputPin,
+    MAPWM: SetDutyCycle,
+    MBIN1: OutputPin,
+    MBIN2: OutputPin,
+    MBPWM: SetDutyCycle,
+    STBY: OutputPin,
+{
+    type Target = Tb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>;
+
+    fn deref(&self) -> &Self::Target {
+        self.controller
+    }
+}
+
+impl<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY> core::ops::DerefMut
+    for StandbyGuard<'_, MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>
+where
+    MAIN1: OutputPin,
+    MAIN2: OutputPin,
+    MAPWM: SetDutyCycle,
+    MBIN1: OutputPin,
+    MBIN2: OutputPin,
+    MBPWM: SetDutyCycle,
+    STBY: OutputPin,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.controller
+    }
+}
+
+impl<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY> Drop
+    for StandbyGuard<'_, MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>
+where
+    MAIN1: OutputPin,
+    MAIN2: OutputPin,
+    MAPWM: SetDutyCycle,
+    MBIN1: OutputPin,
+    MBIN2: OutputPin,
+    MBPWM: SetDutyCycle,
+    STBY: OutputPin,
+{
+    fn drop(&mut self) {
+        if self.restore().is_err() {
+            log_warn!("failed to restore the standby mode");
+        }
+    }
+}
+
+/// Mix the (clamped) arcade-style throttle and steering into the (not yet clamped) speeds of the left and the right
+/// motor, see [`Tb6612fng::drive_arcade`].
+fn mix_arcade(throttle: i8, steering: i8) -> (i16, i16) {
+    let throttle = i16::from(throttle).clamp(-100, 100);
+    let steering = i16::from(steering).clamp(-100, 100);
+
+    (throttle + steering, throttle - steering)
+}
+
+/// A two-wheeled robot with a differential drive, i.e. one which steers by driving its wheels at different speeds.
+///
+/// Motor A of the [`Tb6612fng`] is the left and motor B the right motor. All speeds are given in percentage, with
+/// positive values driving forward and negative values driving backward. They are clamped to -100..=100 and a
+/// resulting speed of 0 [stops](DriveCommand::Stop) the motor.
+///
+/// If a motor has been mounted the other way around (or its wiring is reversed) set the respective inversion flag so
+/// that positive speeds drive the robot forward on both sides.
+#[derive(Debug)]
+pub struct DifferentialDrive<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY> {
+    /// The underlying controller, for direct access (e.g. to control the standby mode)
+    pub controller: Tb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>,
+    /// Whether the direction of the left motor (motor A) is inverted
+    pub left_inverted: bool,
+    /// Whether the direction of the right motor (motor B) is inverted
+    pub right_inverted: bool,
+}
+
+impl<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>
+    DifferentialDrive<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>
+where
+    MAIN1: OutputPin,
+    MAIN2: OutputPin,
+    MAPWM: SetDutyCycle,
+    MBIN1: OutputPin,
+    MBIN2: OutputPin,
+    MBPWM: SetDutyCycle,
+    STBY: OutputPin,
+{
+    /// Create a differential drive using the defined controller. This does not interact with any pin.
+    pub fn new(
+        controller: Tb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>,
+        left_inverted: bool,
+        right_inverted: bool,
+    ) -> DifferentialDrive<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY> {
+        DifferentialDrive {
+            controller,
+            left_inverted,
+            right_inverted,
+        }
+    }
+
+    /// Drive the left and the right motor with the defined speeds.
+    ///
+    /// # Errors
+    /// See [`Tb6612fng::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn drive_tank(
+        &mut self,
+        left: i8,
+        right: i8,
+    ) -> Result<
+        (),
+        Tb6612fngDriveError<
+            MotorError<MAIN1::Error, MAIN2::Error, MAPWM::Error>,
+            MotorError<MBIN1::Error, MBIN2::Error, MBPWM::Error>,
+        >,
+    > {
+        self.drive_mixed(left.into(), right.into())
+    }
+
+    /// Drive with arcade-style controls: the `throttle` defines how fast the robot drives forward (positive values) or
+    /// backward (negative values) and `turn` how much it turns to the right (positive values) or to the left (negative
+    /// values). They are mixed like in [`Tb6612fng::drive_arcade`].
+    ///
+    /// # Errors
+    /// See [`Tb6612fng::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn drive_arcade(
+        &mut self,
+        throttle: i8,
+        turn: i8,
+    ) -> Result<
+        (),
+        Tb6612fngDriveError<
+            MotorError<MAIN1::Error, MAIN2::Error, MAPWM::Error>,
+            MotorError<MBIN1::Error, MBIN2::Error, MBPWM::Error>,
+        >,
+    > {
+        let (left, right) = mix_arcade(throttle, turn);
+        self.drive_mixed(left, right)
+    }
+
+    /// Turn the robot on the spot by driving the motors in opposite directions: positive speeds turn it to the right
+    /// (clockwise), negative speeds to the left (counterclockwise).
+    ///
+    /// # Errors
+    /// See [`Tb6612fng::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn spin_in_place(
+        &mut self,
+        speed: i8,
+    ) -> Result<
+        (),
+        Tb6612fngDriveError<
+            MotorError<MAIN1::Error, MAIN2::Error, MAPWM::Error>,
+            MotorError<MBIN1::Error, MBIN2::Error, MBPWM::Error>,
+        >,
+    > {
+        let speed = i16::from(speed);
+        self.drive_mixed(speed, -speed)
+    }
+
+    /// Drive straight forward (positive speeds) or backward (negative speeds) with both motors at the same speed.
+    ///
+    /// # Errors
+    /// See [`Tb6612fng::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn drive_straight(
+        &mut self,
+        speed: i8,
+    ) -> Result<
+        (),
+        Tb6612fngDriveError<
+            MotorError<MAIN1::Error, MAIN2::Error, MAPWM::Error>,
+            MotorError<MBIN1::Error, MBIN2::Error, MBPWM::Error>,
+        >,
+    > {
+        self.drive_tank(speed, speed)
+    }
+
+    /// Apply the inversion flags to the (not yet clamped) speeds and drive the motors.
+    #[allow(clippy::type_complexity)]
+    fn drive_mixed(
+        &mut self,
+        left: i16,
+        right: i16,
+    ) -> Result<
+        (),
+        Tb6612fngDriveError<
+            MotorError<MAIN1::Error, MAIN2::Error, MAPWM::Error>,
+            MotorError<MBIN1::Error, MBIN2::Error, MBPWM::Error>,
+        >,
+    > {
+        let left = if self.left_inverted { -left } else { left };
+        let right = if self.right_inverted { -right } else { right };
+
+        self.controller.drive_signed_speeds(left, right)
+    }
+
+    /// Release the differential drive, returning the underlying controller.
+    pub fn release(self) -> Tb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY> {
+        self.controller
+    }
+}
+
+/// Represents a single motor (either motor A or motor B) hooked up to a TB6612FNG controller.
+///
+/// This is unaware of the standby pin. If you plan on using both motors and the standby feature then use the [`Tb6612fng`] struct instead.
+/// See the crate-level comment for further details on when to use what.
+#[derive(Debug)]
+pub struct Motor<IN1, IN2, PWM> {
+    in1: IN1,
+    in2: IN2,
+    pwm: PWM,
+    max_duty: u16,
+    max_speed: u8,
+    deadband: u8,
+    current_duty: u16,
+    current_drive_command: DriveCommand,
+    last_failed_drive_command: Option<DriveCommand>,
+    direction_inverted: bool,
+    on_drive: Option<fn(&DriveCommand)>,
+    closed_loop_gains: ClosedLoopGains,
+    closed_loop_integral: f32,
+    pwm_frequency: Option<u32>,
+    last_direction: Option<Direction>,
+    reversal_count: u32,
+    /// The states which have last been written to the IN1 and IN2 pins, `None` if unknown
+    pin_states: (Option<bool>, Option<bool>),
+}
+
+impl<IN1, IN2, PWM> Motor<IN1, IN2, PWM>
+where
+    IN1: OutputPin,
+    IN2: OutputPin,
+    PWM: SetDutyCycle,
+{
+    /// Instantiate a new [`Motor`] with the defined pins.
+    /// This also automatically enables the PWM pin.
+    /// The initial state of the motor will be set to [stopped](DriveCommand::Stop).
+    ///
+    /// Note that the frequency of the PWM signal must be configured in your HAL. The TB6612FNG supports up to 100 kHz,
+    /// frequencies below ~1 kHz usually result in audible buzzing and poor torque. See [`Motor::new_with_frequency`]
+    /// if you want the motor to keep track of the configured frequency.
+    ///
+    /// # Errors
+    /// If any of the underlying pin interactions fail these errors will be propagated up.
+    /// The errors are specific to your HAL.
+    ///
+    /// # Usage example
+    /// ```
+    /// # use embedded_hal_mock::eh1::digital::Mock as PinMock;
+    /// # use embedded_hal_mock::eh1::pwm::Mock as PwmMock;
+    /// # use embedded_hal_mock::eh1::pwm::Transaction as PwmTransaction;
+    /// # use embedded_hal_mock::eh1::digital::Transaction as PinTransaction;
+    /// # use embedded_hal_mock::eh1::digital::State::{Low};
+    /// # let motor_in1 = PinMock::new(&[PinTransaction::set(Low)]);
+    /// # let mut motor_in1_ = motor_in1.clone();
+    /// # let motor_in2 = PinMock::new(&[PinTransaction::set(Low)]);
+    /// # let mut motor_in2_ = motor_in2.clone();
+    /// # let motor_pwm = PwmMock::new(&[PwmTransaction::max_duty_cycle(100), PwmTransaction::set_duty_cycle(0)]);
+    /// # let mut motor_pwm_ = motor_pwm.clone();
+    /// use tb6612fng::Motor;
+    ///
+    /// let motor = Motor::new(
+    ///     motor_in1,
+    ///     motor_in2,
+    ///     motor_pwm,
+    /// );
+    ///
+    /// # motor_in1_.done();
+    /// # motor_in2_.done();
+    /// # motor_pwm_.done();
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn new(
+        in1: IN1,
+        in2: IN2,
+        pwm: PWM,
+    ) -> Result<Motor<IN1, IN2, PWM>, MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        Self::new_with_initial_command(in1, in2, pwm, DriveCommand::Stop)
+    }
+
+    /// Instantiate a new [`Motor`] with the defined pins and apply the defined initial drive command.
+    /// This also automatically enables the PWM pin.
+    ///
+    /// Use this instead of [`Motor::new`] if the motor should start in a state other than [stopped](DriveCommand::Stop).
+    ///
+    /// # Errors
+    /// If any of the underlying pin interactions fail these errors will be propagated up.
+    /// The errors are specific to your HAL.
+    #[allow(clippy::type_complexity)]
+    pub fn new_with_initial_command(
+        in1: IN1,
+        in2: IN2,
+        pwm: PWM,
+        initial_drive_command: DriveCommand,
+    ) -> Result<Motor<IN1, IN2, PWM>, MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        let mut motor = Self::new_unchecked(in1, in2, pwm);
+
+        motor.drive(initial_drive_command)?;
+
+        Ok(motor)
+    }
+
+    /// Instantiate a new [`Motor`] like [`Motor::new`] and store the frequency (in Hz) with which the PWM pin has been
+    /// configured in your HAL, see [`Motor::pwm_frequency`].
+    ///
+    /// This does not change the behaviour of the motor, it only serves diagnostic purposes. If the `defmt` or `log`
+    /// feature is enabled a warning is logged if the frequency is outside the typical range of 1 to 100 kHz.
+    ///
+    /// # Errors
+    /// See [`Motor::new`].
+    #[allow(clippy::type_complexity)]
+    pub fn new_with_frequency(
+        in1: IN1,
+        in2: IN2,
+        pwm: PWM,
+        frequency_hz: u32,
+    ) -> Result<Motor<IN1, IN2, PWM>, MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        if !(1_000..=100_000).contains(&frequency_hz) {
+            log_warn!(
+                "the PWM frequency of {} Hz is outside of the typical range of 1 to 100 kHz",
+                frequency_hz
+            );
+        }
+
+        let mut motor = Self::new(in1, in2, pwm)?;
+        motor.pwm_frequency = Some(frequency_hz);
+
+        Ok(motor)
+    }
+
+    /// Instantiate a new [`Motor`] with the defined pins *without* changing their output.
+    ///
+    /// The motor assumes that it is [stopped](DriveCommand::Stop), but this is not applied to the pins until you
+    /// call [`Motor::init`] (or drive the motor). This allows you to control when the first interaction with
+    /// the hardware happens and, as it cannot fail, avoids having to handle a `Result` during construction.
+    /// Only the [maximum duty cycle](SetDutyCycle::max_duty_cycle) of the PWM pin is read (and cached).
+    pub fn new_unchecked(in1: IN1, in2: IN2, pwm: PWM) -> Motor<IN1, IN2, PWM> {
+        let max_duty = pwm.max_duty_cycle();
+
+        Motor {
+            in1,
+            in2,
+            pwm,
+            max_duty,
+            max_speed: 100,
+            deadband: 0,
+            current_duty: 0,
+            current_drive_command: DriveCommand::Stop,
+            last_failed_drive_command: None,
+            direction_inverted: false,
+            on_drive: None,
+            closed_loop_gains: ClosedLoopGains::default(),
+            closed_loop_integral: 0.0,
+            pwm_frequency: None,
+            last_direction: None,
+            reversal_count: 0,
+            pin_states: (None, None),
+        }
+    }
+
+    /// Instantiate a new [`Motor`] with the defined pins which adopts their current state instead of stopping it, e.g.
+    /// to take over a motor which is still running after a soft reset (without stopping and restarting it).
+    ///
+    /// Like [`Motor::new_unchecked`] this does not change the output of any pin, the caller is trusted that the pins
+    /// actually correspond to the `assumed` drive command (without any [direction inversion](Motor::invert_direction)).
+    /// Only the [maximum duty cycle](SetDutyCycle::max_duty_cycle) of the PWM pin is read (and cached).
+    pub fn new_with_state(
+        in1: IN1,
+        in2: IN2,
+        pwm: PWM,
+        assumed: DriveCommand,
+    ) -> Motor<IN1, IN2, PWM> {
+        let mut motor = Self::new_unchecked(in1, in2, pwm);
+        let (in1_state, in2_state) = assumed.to_pin_states();
+        motor.pin_states = (Some(in1_state), Some(in2_state));
+        motor.current_duty = motor.duty_for_percent(assumed.magnitude());
+        motor.current_drive_command = assumed;
+        if assumed.is_moving() {
+            motor.last_direction = assumed.direction();
+        }
+
+        motor
+    }
+
+    /// Replace the PWM pin with a new one (of the same type) and return the previous one, e.g. to switch to a timer
+    /// running at a different frequency. The drive command and all other settings of the motor are kept.
+    ///
+    /// The [maximum duty cycle](SetDutyCycle::max_duty_cycle) of the new PWM pin is read, but the pin itself is not
+    /// changed. Call [`Motor::init`] (or [`Motor::drive`]) afterwards to apply the current drive command to it.
+    ///
+    /// # Usage example
+    /// ```
+    /// # use embedded_hal_mock::eh1::digital::Mock as PinMock;
+    /// # use embedded_hal_mock::eh1::pwm::Mock as PwmMock;
+    /// # use embedded_hal_mock::eh1::pwm::Transaction as PwmTransaction;
+    /// # use tb6612fng::{DriveCommand, Motor};
+    /// # let in1 = PinMock::new(&[]);
+    /// # let in2 = PinMock::new(&[]);
+    /// # let slow_pwm = PwmMock::new(&[PwmTransaction::max_duty_cycle(100)]);
+    /// # let fast_pwm = PwmMock::new(&[PwmTransaction::max_duty_cycle(50)]);
+    /// # let mut in1_ = in1.clone();
+    /// # let mut in2_ = in2.clone();
+    /// # let mut fast_pwm_ = fast_pwm.clone();
+    /// let mut motor = Motor::new_unchecked(in1, in2, slow_pwm);
+    ///
+    /// let mut slow_pwm = motor.replace_pwm(fast_pwm);
+    /// assert_eq!(motor.max_duty(), 50);
+    /// // call `motor.init()` to apply the current drive command to the new PWM pin
+    /// # slow_pwm.done();
+    /// # in1_.done();
+    /// # in2_.done();
+    /// # fast_pwm_.done();
+    /// ```
+    pub fn replace_pwm(&mut self, pwm: PWM) -> PWM {
+        self.max_duty = pwm.max_duty_cycle();
+        core::mem::replace(&mut self.pwm, pwm)
+    }
+
+    /// Apply the [current drive command](Motor::current_drive_command) to the pins.
+    ///
+    /// This is meant to be called after creating the motor with [`Motor::new_unchecked`] or replacing the PWM pin with
+    /// [`Motor::replace_pwm`].
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn init(&mut self) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        self.drive(self.current_drive_command)
+    }
+
+    /// Drive with the defined speed (or brake or stop the motor).
+    ///
+    /// The IN1 & IN2 pins are only written if their state changes (e.g. when changing the direction), the PWM pin is
+    /// always written, even if the command is the same as the [current one](Motor::current_drive_command).
+    /// If the IN1 & IN2 pins change while the motor is moving the duty cycle is set to 0 before writing them, so that
+    /// the motor is never driven with a mix of the old and new pin states.
+    /// If you call this repeatedly (e.g. in a control loop) consider using [`Motor::drive_if_changed`] instead, which
+    /// skips unchanged commands and tells you whether the pins have been touched.
+    ///
+    /// # Errors
+    /// If the underlying pin interaction fails this error will be propagated up.
+    /// The error is specific to your HAL.
+    ///
+    /// As the speed of a [`DriveCommand`] is always valid this never returns [`MotorError::InvalidSpeed`]. Speeds
+    /// above the [maximum speed](Motor::set_max_speed) are clamped.
+    #[allow(clippy::type_complexity)]
+    pub fn drive(
+        &mut self,
+        drive_command: DriveCommand,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        let drive_command = self.limit_speed(self.apply_deadband(drive_command));
+        let result = self.apply_drive_command(drive_command);
+        self.last_failed_drive_command = result.is_err().then_some(drive_command);
+        result
+    }
+
+    /// Replace drive commands with a speed below the [deadband](Motor::set_deadband) with [`DriveCommand::Stop`].
+    fn apply_deadband(&self, drive_command: DriveCommand) -> DriveCommand {
+        match drive_command {
+            DriveCommand::Forward(s) | DriveCommand::Backward(s) if s.get() < self.deadband => {
+                DriveCommand::Stop
+            }
+            c => c,
+        }
+    }
+
+    /// Clamp the speed of the drive command to the [maximum speed](Motor::set_max_speed).
+    fn limit_speed(&self, drive_command: DriveCommand) -> DriveCommand {
+        match drive_command {
+            DriveCommand::Forward(s) | DriveCommand::Backward(s) if s.get() > self.max_speed => {
+                drive_command.saturating_with_speed(self.max_speed)
+            }
+            c => c,
+        }
+    }
+
+    /// Apply the deadband and the maximum speed to the drive command and check whether it reverses the direction of
+    /// the motor, see [`Motor::drive_with_reversal_guard`].
+    fn check_reversal(&self, drive_command: DriveCommand) -> (DriveCommand, bool) {
+        let drive_command = self.limit_speed(self.apply_deadband(drive_command));
+        let is_reversal = matches!(
+            (self.current_drive_command, drive_command),
+            (DriveCommand::Forward(_), DriveCommand::Backward(_))
+                | (DriveCommand::Backward(_), DriveCommand::Forward(_))
+        );
+        (drive_command, is_reversal)
+    }
+
+    /// Apply the drive command, see [`Motor::drive`].
+    #[allow(clippy::type_complexity)]
+    fn apply_drive_command(
+        &mut self,
+        drive_command: DriveCommand,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        let duty = self.duty_for_percent(drive_command.magnitude());
+        log_debug!("driving motor: {} (duty cycle {})", drive_command, duty);
+
+        self.set_outputs(drive_command, duty)?;
+
+        self.set_current_drive_command(drive_command);
+
+        Ok(())
+    }
+
+    /// Reverse the direction of the motor while keeping its speed, i.e. [`DriveCommand::Forward`] becomes
+    /// [`DriveCommand::Backward`] and vice versa. [`DriveCommand::Brake`] and [`DriveCommand::Stop`] are re-applied
+    /// unchanged.
+    ///
+    /// Note that a speed of 0 is reversed as well, i.e. `Forward(0)` becomes `Backward(0)`. While this doesn't
+    /// move the motor it does change the IN1 and IN2 pins and the direction reported by [`Motor::current_drive_command`].
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn reverse(&mut self) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        self.drive(self.current_drive_command.reverse())
+    }
+
+    /// Change the speed (in percentage) of the motor while keeping its direction.
+    ///
+    /// If the motor is currently [braking](DriveCommand::Brake) or [stopped](DriveCommand::Stop) it has no direction,
+    /// in this case it is driven [forward](DriveCommand::Forward). Drive with a speed of 0 (e.g.
+    /// [`DriveCommand::BACKWARD_STOPPED`]) to set the direction without moving the motor.
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn set_speed(
+        &mut self,
+        speed: Percent,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        match self.current_drive_command {
+            DriveCommand::Backward(_) => self.drive(DriveCommand::Backward(speed)),
+            DriveCommand::Forward(_) | DriveCommand::Brake | DriveCommand::Stop => {
+                self.drive(DriveCommand::Forward(speed))
+            }
+        }
+    }
+
+    /// Increase the speed of the motor by `delta` percent (saturating at 100%) while keeping its direction.
+    ///
+    /// This does nothing if the motor is currently [braking](DriveCommand::Brake) or [stopped](DriveCommand::Stop).
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn increment_speed(
+        &mut self,
+        delta: u8,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        match self.current_drive_command {
+            DriveCommand::Forward(s) | DriveCommand::Backward(s) => self.drive(
+                self.current_drive_command
+                    .saturating_with_speed(s.get().saturating_add(delta)),
+            ),
+            DriveCommand::Brake | DriveCommand::Stop => Ok(()),
+        }
+    }
+
+    /// Decrease the speed of the motor by `delta` percent (saturating at 0%) while keeping its direction.
+    ///
+    /// This does nothing if the motor is currently [braking](DriveCommand::Brake) or [stopped](DriveCommand::Stop).
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn decrement_speed(
+        &mut self,
+        delta: u8,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        match self.current_drive_command {
+            DriveCommand::Forward(s) | DriveCommand::Backward(s) => self.drive(
+                self.current_drive_command
+                    .saturating_with_speed(s.get().saturating_sub(delta)),
+            ),
+            DriveCommand::Brake | DriveCommand::Stop => Ok(()),
+        }
+    }
+
+    /// Move the speed of the motor towards the target by at most `step` percent.
+    ///
+    /// This is meant to be called repeatedly (e.g. once per control loop tick) to accelerate or decelerate the motor
+    /// gradually instead of jumping to the target speed immediately, which avoids large inrush currents.
+    /// When the target requires a change of direction the motor is first slowed down to 0 before it is accelerated
+    /// in the new direction. Once the target speed has been reached the target command itself is applied.
+    ///
+    /// Returns `true` once the target has been reached (i.e. there is no need to call this method again), `false`
+    /// otherwise. Note that a `step` of 0 will never reach the target unless the motor is already there.
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn ramp_to(
+        &mut self,
+        target: DriveCommand,
+        step: u8,
+    ) -> Result<bool, MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        let target = self.limit_speed(self.apply_deadband(target));
+        let current_speed = self.current_speed();
+        let (mut drive_command, reached) = DriveCommand::ramp_step(current_speed, target, step);
+
+        // skip the deadband: jump to its edge when accelerating and stop when decelerating, as the speed would
+        // otherwise never change
+        if drive_command.is_moving() && drive_command.magnitude() < self.deadband {
+            drive_command = match drive_command {
+                _ if drive_command.magnitude() <= current_speed.unsigned_abs() => {
+                    DriveCommand::Stop
+                }
+                _ => drive_command.saturating_with_speed(self.deadband),
+            };
+        }
+
+        self.drive(drive_command)?;
+        Ok(reached || drive_command == target)
+    }
+
+    /// Decelerate the motor by at most `step` percent while keeping its direction and let it
+    /// [coast](DriveCommand::Stop) once the speed has reached 0.
+    ///
+    /// This is meant to be called repeatedly (e.g. once per control loop tick). Unlike driving with
+    /// [`DriveCommand::Stop`] directly this keeps the H-bridge in drive mode during the ramp, which is gentler on
+    /// mechanical loads. A motor which is currently [braking](DriveCommand::Brake) is released to coast immediately.
+    ///
+    /// Returns `true` once the motor has been stopped (i.e. there is no need to call this method again), `false`
+    /// otherwise. Note that a `step` of 0 will never stop the motor unless its speed is already 0.
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn decel_stop(
+        &mut self,
+        step: u8,
+    ) -> Result<bool, MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        match self.current_drive_command {
+            DriveCommand::Forward(s) | DriveCommand::Backward(s) if s.get() > step => {
+                self.drive(
+                    self.current_drive_command
+                        .saturating_with_speed(s.get() - step),
+                )?;
+                Ok(false)
+            }
+            DriveCommand::Stop => Ok(true),
+            DriveCommand::Forward(_) | DriveCommand::Backward(_) | DriveCommand::Brake => {
+                self.drive(DriveCommand::Stop)?;
+                Ok(true)
+            }
+        }
+    }
+
+    /// Drive in the defined direction with the defined raw duty cycle.
+    ///
+    /// Unlike [`Motor::drive`] this does not go through a percentage value but passes the duty cycle as-is
+    /// to the PWM pin, thus allowing the full resolution of the PWM to be used.
+    ///
+    /// [`Motor::current_drive_command`] and [`Motor::current_speed`] will report the duty cycle converted
+    /// to a percentage value (rounded to the nearest integer).
+    ///
+    /// The [maximum speed](Motor::set_max_speed) and the [deadband](Motor::set_deadband) apply as well: the duty
+    /// cycle is clamped to the maximum speed and a duty cycle below the deadband [stops](DriveCommand::Stop) the
+    /// motor instead.
+    ///
+    /// # Errors
+    /// If the underlying pin interaction fails this error will be propagated up.
+    /// The error is specific to your HAL.
+    ///
+    /// The specified duty cycle must not be greater than [`SetDutyCycle::max_duty_cycle`], otherwise you will get a
+    /// [`MotorError::InvalidSpeed`] error.
+    #[allow(clippy::type_complexity)]
+    pub fn drive_raw(
+        &mut self,
+        direction: Direction,
+        duty: u16,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        let max_duty = self.max_duty;
+
+        if duty > max_duty {
+            return Err(MotorError::InvalidSpeed);
+        }
+
+        let duty = duty.min(self.duty_for_percent(self.max_speed));
+
+        if u32::from(duty) * 100 < u32::from(self.deadband) * u32::from(max_duty) {
+            return self.drive(DriveCommand::Stop);
+        }
+
+        let speed = if max_duty == 0 {
+            0
+        } else {
+            ((duty as u32 * 100 + max_duty as u32 / 2) / max_duty as u32) as u8
+        };
+        // the duty cycle has been checked against the maximum duty cycle, so the speed is at most 100
+        let drive_command = DriveCommand::from((direction, Percent(speed)));
+
+        let result = self.set_outputs(drive_command, duty);
+        self.last_failed_drive_command = result.is_err().then_some(drive_command);
+        result?;
+
+        self.set_current_drive_command(drive_command);
+
+        Ok(())
+    }
+
+    /// Drive in the defined direction with the defined speed in tenths of a percent (‰), i.e. between 0 and 1000.
+    ///
+    /// This offers a finer resolution than [`Motor::drive`] while, unlike [`Motor::drive_raw`], staying independent
+    /// of the [maximum duty cycle](SetDutyCycle::max_duty_cycle) of the PWM pin. The speed is converted to the duty
+    /// cycle `speed * max_duty / 1000`, rounded to the nearest integer (halves are rounded up). Thus the resolution
+    /// is limited by the PWM: with a maximum duty cycle below 1000 several speeds map to the same duty cycle.
+    ///
+    /// Apart from this the behaviour is the same as for [`Motor::drive_raw`] (including the rounding of
+    /// [`Motor::current_drive_command`] to a whole percentage value and the [deadband](Motor::set_deadband)).
+    ///
+    /// # Errors
+    /// If the underlying pin interaction fails this error will be propagated up.
+    /// The error is specific to your HAL.
+    ///
+    /// The specified speed must be between 0 and 1000 (inclusive), otherwise you will get a
+    /// [`MotorError::InvalidSpeed`] error.
+    #[allow(clippy::type_complexity)]
+    pub fn drive_permille(
+        &mut self,
+        direction: Direction,
+        speed: u16,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        if speed > 1000 {
+            return Err(MotorError::InvalidSpeed);
+        }
+
+        let duty = (u32::from(speed) * u32::from(self.max_duty) + 500) / 1000;
+        self.drive_raw(direction, duty as u16)
+    }
+
+    /// Set the raw duty cycle of the PWM pin and remember it.
+    ///
+    /// A duty cycle of 0 or the maximum duty cycle is set via [`SetDutyCycle::set_duty_cycle_fully_off`] resp.
+    /// [`SetDutyCycle::set_duty_cycle_fully_on`], which HALs may implement specially (e.g. by forcing the output).
+    #[allow(clippy::type_complexity)]
+    fn set_duty(
+        &mut self,
+        duty: u16,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        if duty == 0 {
+            self.pwm.set_duty_cycle_fully_off()
+        } else if duty == self.max_duty {
+            self.pwm.set_duty_cycle_fully_on()
+        } else {
+            self.pwm.set_duty_cycle(duty)
+        }
+        .map_err(MotorError::PwmError)?;
+        self.current_duty = duty;
+        Ok(())
+    }
+
+    /// Store the drive command which has just been applied and notify the [callback](Motor::set_on_drive) if it changed.
+    fn set_current_drive_command(&mut self, drive_command: DriveCommand) {
+        let changed = drive_command != self.current_drive_command;
+        self.current_drive_command = drive_command;
+
+        if let (true, Some(direction)) = (drive_command.is_moving(), drive_command.direction()) {
+            if self.last_direction.is_some_and(|d| d != direction) {
+                self.reversal_count = self.reversal_count.saturating_add(1);
+            }
+            self.last_direction = Some(direction);
+        }
+
+        if let (true, Some(on_drive)) = (changed, self.on_drive) {
+            on_drive(&drive_command);
+        }
+    }
+
+    /// Set the IN1 and IN2 pins according to the drive command and then the PWM pin to the duty cycle.
+    ///
+    /// If a pin has to change while the PWM pin is active the duty cycle is set to 0 first: the pins are written one
+    /// after the other, so otherwise the motor would briefly be driven with a mix of the old and new pin states
+    /// (e.g. both pins high for a moment when reversing, which shorts the motor while the PWM is still on).
+    #[allow(clippy::type_complexity)]
+    fn set_outputs(
+        &mut self,
+        drive_command: DriveCommand,
+        duty: u16,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        let pwm_off =
+            self.current_duty > 0 && self.pin_states != self.pin_states_for(drive_command);
+        if pwm_off {
+            self.set_duty(0)?;
+        }
+
+        self.set_direction_pins(drive_command)?;
+
+        // don't write the duty cycle twice if it has just been set to 0 anyway
+        if !(pwm_off && duty == 0) {
+            self.set_duty(duty)?;
+        }
+
+        Ok(())
+    }
+
+    /// The states of the IN1 and IN2 pins for the drive command (taking the direction inversion into account).
+    fn pin_states_for(&self, drive_command: DriveCommand) -> (Option<bool>, Option<bool>) {
+        let drive_command = if self.direction_inverted {
+            drive_command.reverse()
+        } else {
+            drive_command
+        };
+        let (in1, in2) = drive_command.to_pin_states();
+        (Some(in1), Some(in2))
+    }
+
+    /// Set the IN1 and IN2 pins according to the drive command (taking the direction inversion into account).
+    ///
+    /// Pins which already are in the required state are not written again, which saves bus traffic e.g. when the pins
+    /// are behind a GPIO expander and only the speed changes.
+    #[allow(clippy::type_complexity)]
+    fn set_direction_pins(
+        &mut self,
+        drive_command: DriveCommand,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        let drive_command = if self.direction_inverted {
+            drive_command.reverse()
+        } else {
+            drive_command
+        };
+        let (in1, in2) = drive_command.to_pin_states();
+
+        if self.pin_states.0 != Some(in1) {
+            // the state is unknown if setting it fails
+            self.pin_states.0 = None;
+            self.in1
+                .set_state(in1.into())
+                .map_err(MotorError::In1Error)?;
+            self.pin_states.0 = Some(in1);
+        }
+        if self.pin_states.1 != Some(in2) {
+            self.pin_states.1 = None;
+            self.in2
+                .set_state(in2.into())
+                .map_err(MotorError::In2Error)?;
+            self.pin_states.1 = Some(in2);
+        }
+
+        Ok(())
+    }
+
+    /// Drive with the command computed by `f` from the [current drive command](Motor::current_drive_command).
+    ///
+    /// This is useful for state machines where the next command is a function of the current one.
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn apply(
+        &mut self,
+        f: impl FnOnce(DriveCommand) -> DriveCommand,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        let drive_command = f(self.current_drive_command);
+        self.drive(drive_command)
+    }
+
+    /// Drive with the defined command like [`Motor::drive`], but only if it differs from the
+    /// [current drive command](Motor::current_drive_command) (including the speed) once the
+    /// [deadband](Motor::set_deadband) and the [maximum speed](Motor::set_max_speed) have been applied to it.
+    /// If the command is unchanged no pin is touched at all, which avoids unnecessary pin writes when this is called
+    /// in a loop.
+    ///
+    /// Returns `true` if the command has been applied and `false` if it was unchanged.
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn drive_if_changed(
+        &mut self,
+        drive_command: DriveCommand,
+    ) -> Result<bool, MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        if self.limit_speed(self.apply_deadband(drive_command)) == self.current_drive_command {
+            return Ok(false);
+        }
+
+        self.drive(drive_command)?;
+        Ok(true)
+    }
+
+    /// Drive with the defined command like [`Motor::drive`], but actively brake first if this reverses the direction
+    /// of the motor (i.e. when switching from [`DriveCommand::Forward`] to [`DriveCommand::Backward`] or vice versa).
+    /// This is gentler on the gearbox and the H-bridge than switching the direction directly.
+    ///
+    /// The `wait` closure is called after the brake has been applied and before the new command is applied,
+    /// allowing you to control how long the motor brakes (e.g. by calling a delay from your HAL in it).
+    /// Note that without an actual delay in `wait` the brake is only momentary and thus has barely any effect.
+    /// `wait` is not called if the direction isn't reversed, which is decided after the
+    /// [deadband](Motor::set_deadband) and the [maximum speed](Motor::set_max_speed) have been applied to the command
+    /// (e.g. a command within the deadband stops the motor without braking first).
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn drive_with_reversal_guard(
+        &mut self,
+        drive_command: DriveCommand,
+        wait: impl FnOnce(),
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        let (drive_command, is_reversal) = self.check_reversal(drive_command);
+
+        if is_reversal {
+            self.drive(DriveCommand::Brake)?;
+            wait();
+        }
+
+        self.drive(drive_command)
+    }
+
+    /// Drive with the defined command for the defined duration (in milliseconds) and then let the motor
+    /// [coast](DriveCommand::Stop).
+    ///
+    /// This is a *blocking* convenience method for simple sequential programs: it only returns once the duration has
+    /// passed (using the provided `delay`).
+    ///
+    /// # Errors
+    /// See [`Motor::drive`]. If applying the command fails because of a pin error the motor is stopped (ignoring any
+    /// further errors) and the original error is returned without waiting, as the pins might have been left in an
+    /// inconsistent state.
+    #[allow(clippy::type_complexity)]
+    pub fn drive_for(
+        &mut self,
+        drive_command: DriveCommand,
+        duration_ms: u32,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        if let Err(e) = self.drive(drive_command) {
+            let _ = self.drive(DriveCommand::Stop);
+            return Err(e);
+        }
+
+        delay.delay_ms(duration_ms);
+
+        self.drive(DriveCommand::Stop)
+    }
+
+    /// Drive in the defined direction with a speed which is guaranteed to be within the defined bounds.
+    ///
+    /// # Errors
+    /// See [`Motor::drive`]. The speed itself is always valid.
+    #[allow(clippy::type_complexity)]
+    pub fn drive_bounded<const MIN: u8, const MAX: u8>(
+        &mut self,
+        direction: Direction,
+        speed: BoundedSpeed<MIN, MAX>,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        self.drive(DriveCommand::from((direction, Percent::from(speed))))
+    }
+
+    /// Drive forward with the defined speed (in percentage).
+    ///
+    /// This is a shorthand for calling [`Motor::drive`] with [`DriveCommand::Forward`].
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[doc(alias = "forward")]
+    #[allow(clippy::type_complexity)]
+    pub fn drive_forward(
+        &mut self,
+        speed: Percent,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        self.drive(DriveCommand::Forward(speed))
+    }
+
+    /// Drive backward with the defined speed (in percentage).
+    ///
+    /// This is a shorthand for calling [`Motor::drive`] with [`DriveCommand::Backward`].
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[doc(alias = "backward")]
+    #[allow(clippy::type_complexity)]
+    pub fn drive_backward(
+        &mut self,
+        speed: Percent,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        self.drive(DriveCommand::Backward(speed))
+    }
+
+    /// Drive with the defined signed speed (in percentage): positive values drive forward, negative values drive
+    /// backward and 0 [stops](DriveCommand::Stop) the motor.
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    ///
+    /// The specified speed must be between -100 and 100 (inclusive), otherwise you will get a
+    /// [`MotorError::InvalidSpeed`] error.
+    #[allow(clippy::type_complexity)]
+    pub fn drive_signed(
+        &mut self,
+        speed: i8,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        self.drive(DriveCommand::from_signed_speed(speed)?)
+    }
+
+    /// Actively brake the motor.
+    ///
+    /// This is a shorthand for calling [`Motor::drive`] with [`DriveCommand::Brake`].
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn brake(&mut self) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        self.drive(DriveCommand::Brake)
+    }
+
+    /// Let the motor coast, i.e. stop it without actively braking.
+    ///
+    /// This is a shorthand for calling [`Motor::drive`] with [`DriveCommand::Stop`].
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[doc(alias = "stop")]
+    #[allow(clippy::type_complexity)]
+    pub fn coast(&mut self) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        self.drive(DriveCommand::Stop)
+    }
+
+    /// Stop the motor (see [`DriveCommand::Stop`]) and then fully disable the PWM output, e.g. to quiesce the timer
+    /// in a low-power sleep mode. Unlike driving with a speed of 0 this turns off the PWM channel completely.
+    ///
+    /// Call [`Motor::enable`] before driving the motor again.
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn disable(&mut self) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>>
+    where
+        PWM: PwmEnable,
+    {
+        self.drive(DriveCommand::Stop)?;
+        self.pwm.disable().map_err(MotorError::PwmError)
+    }
+
+    /// Re-enable the PWM output after it has been disabled with [`Motor::disable`].
+    ///
+    /// The motor remains stopped until the next call to [`Motor::drive`].
+    ///
+    /// # Errors
+    /// Returns [`MotorError::PwmError`] if the PWM output could not be enabled.
+    #[allow(clippy::type_complexity)]
+    pub fn enable(&mut self) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>>
+    where
+        PWM: PwmEnable,
+    {
+        self.pwm.enable().map_err(MotorError::PwmError)
+    }
+
+    /// Set the maximum speed (in percentage) of the motor. All speeds above it will be reduced to it, e.g. to protect
+    /// the hardware from overheating. Values above 100 are treated as 100 (which is the default).
+    ///
+    /// Unlike speeds above 100, speeds above the maximum speed are not rejected but silently clamped and
+    /// [`Motor::current_drive_command`] and [`Motor::current_speed`] report the clamped speed which has actually been
+    /// applied.
+    ///
+    /// Note that this does not change the current output of the pins, the new setting is only applied on the
+    /// next call to [`Motor::drive`].
+    pub fn set_max_speed(&mut self, max_speed: u8) {
+        self.max_speed = max_speed.min(100);
+    }
+
+    /// Returns the maximum speed (in percentage) of the motor, see [`Motor::set_max_speed`].
+    pub fn max_speed(&self) -> u8 {
+        self.max_speed
+    }
+
+    /// Set the deadband (in percentage) of the motor: all requested speeds below it [stop](DriveCommand::Stop) the
+    /// motor instead, e.g. to ignore the noise of an analog joystick around its center. A deadband of 0 (which is the
+    /// default) disables this.
+    ///
+    /// [`Motor::current_drive_command`] and [`Motor::current_speed`] report the command which has actually been
+    /// applied, i.e. [`DriveCommand::Stop`] and 0. [`Motor::ramp_to`] skips the speeds within the deadband. The
+    /// deadband also applies to [`Motor::drive_raw`] and [`Motor::drive_permille`], based on their duty cycle.
+    ///
+    /// Note that this does not change the current output of the pins, the new setting is only applied on the
+    /// next call to [`Motor::drive`].
+    pub fn set_deadband(&mut self, deadband: u8) {
+        self.deadband = deadband;
+    }
+
+    /// Returns the deadband (in percentage) of the motor, see [`Motor::set_deadband`].
+    pub fn deadband(&self) -> u8 {
+        self.deadband
+    }
+
+    /// Set a callback which is called whenever a different drive command has been applied successfully.
+    ///
+    /// This can e.g. be used to log all changes with the logging framework of your choice or to record them for
+    /// later analysis. The callback is not called if the same command is applied again.
+    pub fn set_on_drive(&mut self, on_drive: fn(&DriveCommand)) {
+        self.on_drive = Some(on_drive);
+    }
+
+    /// Remove the callback set with [`Motor::set_on_drive`].
+    pub fn clear_on_drive(&mut self) {
+        self.on_drive = None;
+    }
+
+    /// Set the gains used by [`Motor::update_closed_loop`]. This also resets the accumulated integral term,
+    /// see [`Motor::reset_closed_loop`].
+    pub fn set_closed_loop_gains(&mut self, gains: ClosedLoopGains) {
+        self.closed_loop_gains = gains;
+        self.reset_closed_loop();
+    }
+
+    /// Returns the gains used by [`Motor::update_closed_loop`].
+    pub fn closed_loop_gains(&self) -> ClosedLoopGains {
+        self.closed_loop_gains
+    }
+
+    /// Reset the integral term accumulated by [`Motor::update_closed_loop`], e.g. when switching to a new target
+    /// or after having driven the motor in open loop mode.
+    pub fn reset_closed_loop(&mut self) {
+        self.closed_loop_integral = 0.0;
+    }
+
+    /// Drive the motor so that it tracks the target speed (in RPM) based on the measured speed (in RPM), e.g. as
+    /// reported by an encoder. Negative values mean driving backward.
+    ///
+    /// This is a simple proportional-integral controller using the [gains](Motor::set_closed_loop_gains) of this
+    /// motor. It is meant to be called periodically (with a fixed interval) with the latest measurement.
+    /// The resulting speed is clamped to the valid range (and to the [maximum speed](Motor::set_max_speed)) and the
+    /// integral term is limited to ±100% to avoid winding up while the motor is saturated.
+    /// See [`Motor::update_closed_loop_from`] to read the measurement from a [`SpeedFeedback`] source instead.
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn update_closed_loop(
+        &mut self,
+        target_rpm: i32,
+        measured_rpm: i32,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        let error = target_rpm.saturating_sub(measured_rpm) as f32;
+
+        self.closed_loop_integral =
+            (self.closed_loop_integral + self.closed_loop_gains.ki * error).clamp(-100.0, 100.0);
+        let speed = self.closed_loop_gains.kp * error + self.closed_loop_integral;
+
+        self.drive(DriveCommand::from_signed_speed_clamped(
+            speed.clamp(-100.0, 100.0) as i16,
+        ))
+    }
+
+    /// Read the measured speed from the `feedback` source and drive the motor so that it tracks the target speed
+    /// (in RPM) like [`Motor::update_closed_loop`].
+    ///
+    /// # Errors
+    /// Returns [`ClosedLoopError::Feedback`] (without driving the motor) if the speed can't be measured and
+    /// [`ClosedLoopError::Motor`] with the errors of [`Motor::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn update_closed_loop_from<F: SpeedFeedback>(
+        &mut self,
+        target_rpm: i32,
+        feedback: &mut F,
+    ) -> Result<(), ClosedLoopError<F::Error, MotorError<IN1::Error, IN2::Error, PWM::Error>>> {
+        let measured_rpm = feedback.measured_rpm().map_err(ClosedLoopError::Feedback)?;
+        self.update_closed_loop(target_rpm, measured_rpm)
+            .map_err(ClosedLoopError::Motor)
+    }
+
+    /// Invert the direction of the motor, i.e. swap how [`DriveCommand::Forward`] and [`DriveCommand::Backward`]
+    /// map to the IN1 and IN2 pins. This is useful if the motor has been wired (or mounted) the other way around.
+    ///
+    /// All other methods (e.g. [`Motor::current_drive_command`] and [`Motor::current_speed`]) keep reporting the
+    /// logical direction which has been requested, not the physical state of the pins.
+    ///
+    /// Note that this does not change the current output of the pins, the new setting is only applied on the
+    /// next call to [`Motor::drive`].
+    pub fn invert_direction(&mut self, inverted: bool) {
+        self.direction_inverted = inverted;
+    }
+
+    /// Returns whether the direction of the motor is inverted, see [`Motor::invert_direction`].
+    pub fn is_direction_inverted(&self) -> bool {
+        self.direction_inverted
+    }
+
+    /// Capture the current state of the motor, see [`Motor::restore_state`].
+    pub fn capture_state(&self) -> MotorState {
+        MotorState {
+            drive_command: self.current_drive_command,
+        }
+    }
+
+    /// Restore a state which has previously been captured with [`Motor::capture_state`] by applying it to the pins,
+    /// e.g. after a bus reset.
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[allow(clippy::type_complexity)]
+    pub fn restore_state(
+        &mut self,
+        state: MotorState,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        self.drive(state.drive_command)
+    }
+
+    /// Returns the direction in which the motor is currently being driven, `None` if it is
+    /// [braking](DriveCommand::Brake) or [stopped](DriveCommand::Stop). See [`DriveCommand::direction`].
+    pub fn direction(&self) -> Option<Direction> {
+        self.current_drive_command.direction()
+    }
+
+    /// Returns whether the motor is currently moving, i.e. driving forward or backward with a speed above 0.
+    pub fn is_moving(&self) -> bool {
+        self.current_drive_command.is_moving()
+    }
+
+    /// Returns whether the motor is currently actively braking.
+    pub fn is_braking(&self) -> bool {
+        self.current_drive_command.is_braking()
+    }
+
+    /// Returns whether the motor is currently stopped without actively braking, i.e. it is either
+    /// [stopped](DriveCommand::Stop) or driving forward or backward with a speed of 0.
+    ///
+    /// Use [`Motor::is_coasting`] if you only want to know whether the motor has been [stopped](DriveCommand::Stop).
+    pub fn is_stopped(&self) -> bool {
+        self.current_drive_command.is_stopped()
+    }
+
+    /// Returns whether the motor is currently coasting, i.e. it has been [stopped](DriveCommand::Stop) and is
+    /// freewheeling. Together with [`Motor::is_braking`] this tells you whether a motor which isn't driven is
+    /// actively held or not.
+    pub fn is_coasting(&self) -> bool {
+        self.current_drive_command.is_coasting()
+    }
+
+    /// Returns the raw duty cycle which is written to the PWM pin for the defined speed (in percentage).
+    ///
+    /// This is based on the [maximum duty cycle](SetDutyCycle::max_duty_cycle) of the PWM pin, which is read once
+    /// when the motor is created. The value is rounded down, so on PWMs with a low resolution multiple speeds might
+    /// result in the same duty cycle. Speeds above 100% are treated as 100%.
+    pub fn duty_for_percent(&self, percent: u8) -> u16 {
+        (u32::from(percent.min(100)) * u32::from(self.max_duty) / 100) as u16
+    }
+
+    /// Returns the [maximum duty cycle](SetDutyCycle::max_duty_cycle) of the PWM pin, which has been read when
+    /// creating the motor (or when [replacing the PWM pin](Motor::replace_pwm)).
+    pub fn max_duty(&self) -> u16 {
+        self.max_duty
+    }
+
+    /// Returns the raw duty cycle which has last been written to the PWM pin.
+    ///
+    /// Together with [`Motor::max_duty`] this allows you to calculate the exact output of the PWM, which
+    /// might differ slightly from [`Motor::current_speed`] due to rounding (see [`Motor::duty_for_percent`]).
+    pub fn current_duty_raw(&self) -> u16 {
+        self.current_duty
+    }
+
+    /// Returns the frequency (in Hz) of the PWM pin if it has been specified with [`Motor::new_with_frequency`].
+    pub fn pwm_frequency(&self) -> Option<u32> {
+        self.pwm_frequency
+    }
+
+    /// Get the last drive command which failed to be applied, if the last attempt to drive the motor failed.
+    /// This is reset to `None` once a drive command has been applied successfully.
+    ///
+    /// This is useful for recovery logic (e.g. retrying the exact command after resetting a GPIO port expander),
+    /// especially for methods which compute the drive command themselves (like [`Motor::ramp_to`]).
+    ///
+    /// Note that when a pin interaction fails the pins may have been changed partially, i.e. the physical state of the
+    /// motor is unknown while [`Motor::current_drive_command`] still reports the last successfully applied command.
+    pub fn last_failed_drive_command(&self) -> Option<DriveCommand> {
+        self.last_failed_drive_command
+    }
+
+    /// Returns how often the motor has reversed its direction since it has been created (or since the last call to
+    /// [`Motor::reset_reversal_count`]), e.g. to estimate the wear of a gearbox.
+    ///
+    /// Only actual direction changes are counted: changing the speed does not count, and neither does stopping or
+    /// braking in-between, i.e. `Forward(50)` → `Brake` → `Backward(50)` is one reversal and `Forward(50)` → `Brake` →
+    /// `Forward(50)` is none. A speed of 0 is not considered to be a direction. The counter saturates at [`u32::MAX`].
+    pub fn reversal_count(&self) -> u32 {
+        self.reversal_count
+    }
+
+    /// Reset the [reversal count](Motor::reversal_count) to 0. The current direction is kept, i.e. the next reversal
+    /// will be counted.
+    pub fn reset_reversal_count(&mut self) {
+        self.reversal_count = 0;
+    }
+
+    /// Get the currently active drive command.
+    ///
+    /// If you only want to know the speed consider calling [`Motor::current_speed`] instead.
+    pub fn current_drive_command(&self) -> &DriveCommand {
+        &self.current_drive_command
+    }
+
+    /// Return the current speed of the motor (in percentage). Note that driving forward returns a positive number
+    /// while driving backward returns a negative number and both [`DriveCommand::Brake`] and [`DriveCommand::Stop`] return 0.
+    ///
+    /// If you need to know in more details what the current status is, consider calling [`Motor::current_drive_command`] instead.
+    pub fn current_speed(&self) -> i8 {
+        self.current_drive_command.signed_speed()
+    }
+
+    /// Release the motor, returning the IN1, IN2 and PWM pins so that they can be used for something else.
+    ///
+    /// Note that this does not change the output of the pins, i.e. they remain in the state of the last drive command.
+    #[doc(alias = "into_parts")]
+    pub fn release(self) -> (IN1, IN2, PWM) {
+        (self.in1, self.in2, self.pwm)
+    }
+}
+
+/// Represents an arbitrary number of motors (e.g. from several TB6612FNG controllers) which can be controlled uniformly.
+///
+/// All motors must use the same pin types. Note that this is unaware of any standby pins, you have to control them
+/// manually.
+#[derive(Debug)]
+pub struct MotorArray<IN1, IN2, PWM, const N: usize> {
+    motors: [Motor<IN1, IN2, PWM>; N],
+}
+
+impl<IN1, IN2, PWM, const N: usize> MotorArray<IN1, IN2, PWM, N>
+where
+    IN1: OutputPin,
+    IN2: OutputPin,
+    PWM: SetDutyCycle,
+{
+    /// Instantiate a new [`MotorArray`] from already initialised motors.
+    pub fn new(motors: [Motor<IN1, IN2, PWM>; N]) -> MotorArray<IN1, IN2, PWM, N> {
+        MotorArray { motors }
+    }
+
+    /// Drive all motors with the defined commands, in the order of their index.
+    ///
+    /// # Errors
+    /// If driving a motor fails the error will be propagated up, telling you the index of the motor which failed.
+    /// See [`Motor::drive`] for the possible errors.
+    ///
+    /// If driving a motor fails then the subsequent motors will not be driven anymore, i.e. they keep their previous
+    /// command.
+    #[allow(clippy::type_complexity)]
+    pub fn drive_all(
+        &mut self,
+        drive_commands: &[DriveCommand; N],
+    ) -> Result<(), MotorArrayDriveError<MotorError<IN1::Error, IN2::Error, PWM::Error>>> {
+        for (index, (motor, drive_command)) in
+            self.motors.iter_mut().zip(drive_commands).enumerate()
+        {
+            motor
+                .drive(*drive_command)
+                .map_err(|error| MotorArrayDriveError { index, error })?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the motor at the defined index or `None` if the index is out of bounds.
+    pub fn get(&self, index: usize) -> Option<&Motor<IN1, IN2, PWM>> {
+        self.motors.get(index)
+    }
+
+    /// Returns the motor at the defined index mutably or `None` if the index is out of bounds.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Motor<IN1, IN2, PWM>> {
+        self.motors.get_mut(index)
+    }
+
+    /// Returns all motors.
+    pub fn motors(&self) -> &[Motor<IN1, IN2, PWM>; N] {
+        &self.motors
+    }
+
+    /// Returns all motors mutably.
+    pub fn motors_mut(&mut self) -> &mut [Motor<IN1, IN2, PWM>; N] {
+        &mut self.motors
+    }
+
+    /// Release the motor array, returning the motors so that they can be used for something else.
+    ///
+    /// Note that this does not change the output of any pin.
+    #[doc(alias = "into_parts")]
+    pub fn release(self) -> [Motor<IN1, IN2, PWM>; N] {
+        self.motors
+    }
+}
+
+/// A sequence of drive commands which are applied to a [`Motor`] one after another, each for a defined duration
+/// (in milliseconds), e.g. to play a choreographed motion.
+///
+/// The sequence only borrows the steps, thus it can be reused (e.g. for both motors).
+///
+/// # Usage example
+/// ```
+/// # use tb6612fng::{DriveCommand, Sequence};
+/// const STEPS: [(DriveCommand, u32); 3] = [
+///     (DriveCommand::forward_const(50), 500),
+///     (DriveCommand::Brake, 100),
+///     (DriveCommand::backward_const(50), 500),
+/// ];
+/// let sequence = Sequence::new(&STEPS);
+/// // sequence.run(&mut motor, &mut delay)?;
+/// # assert_eq!(sequence.steps().len(), 3);
+/// ```
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+pub struct Sequence<'a> {
+    steps: &'a [(DriveCommand, u32)],
+}
+
+impl<'a> Sequence<'a> {
+    /// Create a new sequence from the defined steps, each consisting of a drive command and the duration (in
+    /// milliseconds) for which it is applied.
+    pub const fn new(steps: &'a [(DriveCommand, u32)]) -> Sequence<'a> {
+        Sequence { steps }
+    }
+
+    /// Returns the steps of this sequence.
+    pub const fn steps(&self) -> &'a [(DriveCommand, u32)] {
+        self.steps
+    }
+
+    /// Run the sequence on the defined motor and let it [coast](DriveCommand::Stop) at the end.
+    ///
+    /// This is *blocking*: it only returns once all steps have been executed (using the provided `delay`).
+    ///
+    /// # Errors
+    /// See [`Motor::drive`]. If a step fails the sequence is aborted and the motor is stopped (ignoring any further
+    /// errors) before the error is returned.
+    #[allow(clippy::type_complexity)]
+    pub fn run<IN1, IN2, PWM>(
+        &self,
+        motor: &mut Motor<IN1, IN2, PWM>,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>>
+    where
+        IN1: OutputPin,
+        IN2: OutputPin,
+        PWM: SetDutyCycle,
+    {
+        for (drive_command, duration_ms) in self.steps {
+            if let Err(e) = motor.drive(*drive_command) {
+                let _ = motor.drive(DriveCommand::Stop);
+                return Err(e);
+            }
+            delay.delay_ms(*duration_ms);
+        }
+
+        motor.drive(DriveCommand::Stop)
+    }
+}
+
+/// Something which can be driven like a [`Motor`], e.g. a motor wrapped in one of the adapters of this crate.
+///
+/// This allows writing code (and wrappers) which works with any motor, independent of the pins it uses.
+///
+/// It is also implemented for mutable references, so you can pass a single channel of a [`Tb6612fng`]
+/// (e.g. `&mut controller.motor_a` or the result of [`Tb6612fng::motor_a`]) to generic code without giving up
+/// ownership of the controller.
+#[doc(alias = "MotorControl")]
+pub trait DriveMotor {
+    /// The error which can happen when driving the motor.
+    type Error;
+
+    /// Drive with the defined command.
+    ///
+    /// # Errors
+    /// See the implementation, e.g. [`Motor::drive`].
+    fn drive(&mut self, drive_command: DriveCommand) -> Result<(), Self::Error>;
+
+    /// Get the currently active drive command.
+    fn current_drive_command(&self) -> &DriveCommand;
+
+    /// Returns the current speed as a signed value (see [`DriveCommand::signed_speed`]).
+    fn current_speed(&self) -> i8 {
+        self.current_drive_command().signed_speed()
+    }
+
+    /// Let the motor coast by driving with [`DriveCommand::Stop`].
+    ///
+    /// Note that this goes through [`DriveMotor::drive`], so wrappers apply their behaviour to it as well
+    /// (e.g. a [`RampingMotor`] slows down gradually).
+    ///
+    /// # Errors
+    /// See [`DriveMotor::drive`].
+    fn stop(&mut self) -> Result<(), Self::Error> {
+        self.drive(DriveCommand::Stop)
+    }
+}
+
+impl<M: DriveMotor + ?Sized> DriveMotor for &mut M {
+    type Error = M::Error;
+
+    fn drive(&mut self, drive_command: DriveCommand) -> Result<(), Self::Error> {
+        (**self).drive(drive_command)
+    }
+
+    fn current_drive_command(&self) -> &DriveCommand {
+        (**self).current_drive_command()
+    }
+
+    fn current_speed(&self) -> i8 {
+        (**self).current_speed()
+    }
+
+    fn stop(&mut self) -> Result<(), Self::Error> {
+        (**self).stop()
+    }
+}
+
+impl<IN1, IN2, PWM> DriveMotor for Motor<IN1, IN2, PWM>
+where
+    IN1: OutputPin,
+    IN2: OutputPin,
+    PWM: SetDutyCycle,
+{
+    type Error = MotorError<IN1::Error, IN2::Error, PWM::Error>;
+
+    fn drive(&mut self, drive_command: DriveCommand) -> Result<(), Self::Error> {
+        Motor::drive(self, drive_command)
+    }
+
+    fn current_drive_command(&self) -> &DriveCommand {
+        Motor::current_drive_command(self)
+    }
+
+    fn current_speed(&self) -> i8 {
+        Motor::current_speed(self)
+    }
+}
+
+/// Wraps a [`DriveMotor`] and changes its speed gradually instead of jumping to the requested speed immediately,
+/// which avoids stalling the motor and mechanical shocks.
+///
+/// Each call to [`RampingMotor::drive`] moves the speed by at most the configured step towards the requested command,
+/// so it has to be called repeatedly (e.g. once per control loop tick) until [`RampingMotor::is_target_reached`]
+/// returns `true`. A change of direction ramps through 0 like [`Motor::ramp_to`]. Once the target speed has been
+/// reached the requested command itself is applied, i.e. [`DriveCommand::Brake`] is only applied at the end.
+///
+/// # Usage example
+/// ```
+/// # use embedded_hal_mock::eh1::digital::Mock as PinMock;
+/// # use embedded_hal_mock::eh1::digital::Transaction as PinTransaction;
+/// # use embedded_hal_mock::eh1::digital::State::{High, Low};
+/// # use embedded_hal_mock::eh1::pwm::Mock as PwmMock;
+/// # use embedded_hal_mock::eh1::pwm::Transaction as PwmTransaction;
+/// # use tb6612fng::{DriveCommand, Motor, RampingMotor};
+/// # let in1 = PinMock::new(&[PinTransaction::set(High)]);
+/// # let in2 = PinMock::new(&[PinTransaction::set(Low)]);
+/// # let pwm = PwmMock::new(&[
+/// #     PwmTransaction::max_duty_cycle(100),
+/// #     PwmTransaction::set_duty_cycle(40),
+/// #     PwmTransaction::set_duty_cycle(60),
+/// # ]);
+/// # let (mut in1_, mut in2_, mut pwm_) = (in1.clone(), in2.clone(), pwm.clone());
+/// let mut motor = RampingMotor::new(Motor::new_unchecked(in1, in2, pwm), 40);
+///
+/// // call this once per control loop tick
+/// motor.drive(DriveCommand::forward_const(60))?;
+/// assert_eq!(*motor.current_drive_command(), DriveCommand::forward_const(40));
+/// assert!(!motor.is_target_reached());
+///
+/// motor.drive(DriveCommand::forward_const(60))?;
+/// assert_eq!(*motor.current_drive_command(), DriveCommand::forward_const(60));
+/// assert!(motor.is_target_reached());
+/// # in1_.done();
+/// # in2_.done();
+/// # pwm_.done();
+/// # Ok::<(), tb6612fng::MotorError<_, _, _>>(())
+/// ```
+#[derive(Debug)]
+pub struct RampingMotor<M> {
+    inner: M,
+    max_step_per_drive_call: u8,
+    target: Option<DriveCommand>,
+    /// The command of the last step, which the wrapped motor may have applied differently (e.g. due to a deadband).
+    ramp_position: DriveCommand,
+    /// The command which the wrapped motor reported after the last step, to notice if it has been driven directly.
+    applied: DriveCommand,
+}
+
+impl<M: DriveMotor> RampingMotor<M> {
+    /// Wrap the motor, changing its speed by at most `max_step_per_drive_call` percent per call to
+    /// [`RampingMotor::drive`]. Note that a step of 0 will never change the speed.
+    pub fn new(inner: M, max_step_per_drive_call: u8) -> RampingMotor<M> {
+        let current_drive_command = *inner.current_drive_command();
+        RampingMotor {
+            inner,
+            max_step_per_drive_call,
+            target: None,
+            ramp_position: current_drive_command,
+            applied: current_drive_command,
+        }
+    }
+
+    /// Move the motor one step towards the defined drive command.
+    ///
+    /// The steps are based on the previous step rather than on the command which the wrapped motor reports, so
+    /// that the ramp progresses even if the wrapped motor changes the commands (e.g. a [`DeadbandMotor`] stopping
+    /// the motor for the first steps or a [`Motor`] limiting the speed to its [maximum](Motor::set_max_speed)).
+    /// If the wrapped motor has been driven directly in the meantime the ramp continues from its current command.
+    ///
+    /// # Errors
+    /// See the [`DriveMotor::drive`] implementation of the wrapped motor.
+    pub fn drive(&mut self, drive_command: DriveCommand) -> Result<(), M::Error> {
+        if *self.inner.current_drive_command() != self.applied {
+            self.ramp_position = *self.inner.current_drive_command();
+        }
+
+        self.target = Some(drive_command);
+        let (drive_command, _) = DriveCommand::ramp_step(
+            self.ramp_position.signed_speed(),
+            drive_command,
+            self.max_step_per_drive_call,
+        );
+        self.inner.drive(drive_command)?;
+        self.ramp_position = drive_command;
+        self.applied = *self.inner.current_drive_command();
+        Ok(())
+    }
+
+    /// Returns the drive command which has last been requested via [`RampingMotor::drive`], if any.
+    pub fn target(&self) -> Option<DriveCommand> {
+        self.target
+    }
+
+    /// Returns whether the ramp has arrived at the requested drive command (or if none has been requested yet), i.e.
+    /// whether there is no need to call [`RampingMotor::drive`] again.
+    ///
+    /// Note that the wrapped motor may apply the command differently, e.g. with a lower speed due to its
+    /// [maximum speed](Motor::set_max_speed).
+    pub fn is_target_reached(&self) -> bool {
+        self.target.map_or(true, |target| {
+            self.ramp_position == target && *self.inner.current_drive_command() == self.applied
+        })
+    }
+
+    /// Get the currently active drive command of the wrapped motor.
+    pub fn current_drive_command(&self) -> &DriveCommand {
+        self.inner.current_drive_command()
+    }
+
+    /// Returns a reference to the wrapped motor.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped motor, e.g. to drive it directly without ramping (the next call to
+    /// [`RampingMotor::drive`] then ramps from the command applied this way).
+    pub fn inner_mut(&mut self) -> &mut M {
+        &mut self.inner
+    }
+
+    /// Release the wrapped motor.
+    pub fn release(self) -> M {
+        self.inner
+    }
+}
+
+impl<M: DriveMotor> DriveMotor for RampingMotor<M> {
+    type Error = M::Error;
+
+    /// See [`RampingMotor::drive`].
+    fn drive(&mut self, drive_command: DriveCommand) -> Result<(), Self::Error> {
+        RampingMotor::drive(self, drive_command)
+    }
+
+    fn current_drive_command(&self) -> &DriveCommand {
+        self.inner.current_drive_command()
+    }
+
+    fn current_speed(&self) -> i8 {
+        self.inner.current_speed()
+    }
+}
+
+/// Wraps a [`DriveMotor`] and [stops](DriveCommand::Stop) it instead of driving it with a speed below the deadband,
+/// e.g. for motors which do not move at low speeds due to static friction.
+///
+/// Speeds at or above the deadband are passed on unmodified, as are [`DriveCommand::Brake`] and
+/// [`DriveCommand::Stop`]. A deadband of 0 disables this.
+///
+/// # Usage example
+/// ```
+/// # use embedded_hal_mock::eh1::digital::Mock as PinMock;
+/// # use embedded_hal_mock::eh1::digital::Transaction as PinTransaction;
+/// # use embedded_hal_mock::eh1::digital::State::Low;
+/// # use embedded_hal_mock::eh1::pwm::Mock as PwmMock;
+/// # use embedded_hal_mock::eh1::pwm::Transaction as PwmTransaction;
+/// # use tb6612fng::{DeadbandMotor, DriveCommand, Motor};
+/// # let in1 = PinMock::new(&[PinTransaction::set(Low)]);
+/// # let in2 = PinMock::new(&[PinTransaction::set(Low)]);
+/// # let pwm = PwmMock::new(&[PwmTransaction::max_duty_cycle(100), PwmTransaction::set_duty_cycle(0)]);
+/// # let (mut in1_, mut in2_, mut pwm_) = (in1.clone(), in2.clone(), pwm.clone());
+/// let mut motor = DeadbandMotor::new(Motor::new_unchecked(in1, in2, pwm), 15);
+///
+/// motor.drive(DriveCommand::forward_const(10))?;
+/// assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+/// # in1_.done();
+/// # in2_.done();
+/// # pwm_.done();
+/// # Ok::<(), tb6612fng::MotorError<_, _, _>>(())
+/// ```
+#[derive(Debug)]
+pub struct DeadbandMotor<M> {
+    inner: M,
+    deadband: u8,
+}
+
+impl<M: DriveMotor> DeadbandMotor<M> {
+    /// Wrap the motor, stopping it for all speeds below `deadband` (in percentage).
+    pub fn new(inner: M, deadband: u8) -> DeadbandMotor<M> {
+        DeadbandMotor { inner, deadband }
+    }
+
+    /// Drive the motor with the defined command, or stop it if the speed is below the deadband.
+    ///
+    /// # Errors
+    /// See the [`DriveMotor::drive`] implementation of the wrapped motor.
+    pub fn drive(&mut self, drive_command: DriveCommand) -> Result<(), M::Error> {
+        let drive_command = match drive_command {
+            DriveCommand::Forward(s) | DriveCommand::Backward(s) if s.get() < self.deadband => {
+                DriveCommand::Stop
+            }
+            c => c,
+        };
+        self.inner.drive(drive_command)
+    }
+
+    /// Returns the deadband (in percentage).
+    pub fn deadband(&self) -> u8 {
+        self.deadband
+    }
+
+    /// Get the currently active drive command of the wrapped motor.
+    pub fn current_drive_command(&self) -> &DriveCommand {
+        self.inner.current_drive_command()
+    }
+
+    /// Returns a reference to the wrapped motor.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped motor, e.g. to drive it directly without the deadband.
+    pub fn inner_mut(&mut self) -> &mut M {
+        &mut self.inner
+    }
+
+    /// Release the wrapped motor.
+    pub fn release(self) -> M {
+        self.inner
+    }
+}
+
+impl<M: DriveMotor> DriveMotor for DeadbandMotor<M> {
+    type Error = M::Error;
+
+    /// See [`DeadbandMotor::drive`].
+    fn drive(&mut self, drive_command: DriveCommand) -> Result<(), Self::Error> {
+        DeadbandMotor::drive(self, drive_command)
+    }
+
+    fn current_drive_command(&self) -> &DriveCommand {
+        self.inner.current_drive_command()
+    }
+
+    fn current_speed(&self) -> i8 {
+        self.inner.current_speed()
+    }
+}
+
+/// Wraps a [`DriveMotor`] which has been mounted (or wired) the other way around and swaps
+/// [`DriveCommand::Forward`] and [`DriveCommand::Backward`] before passing the commands on to it. [`DriveCommand::Brake`]
+/// and [`DriveCommand::Stop`] are passed on unchanged.
+///
+/// [`ReversedMotor::current_drive_command`] reports the command which the wrapped motor has actually applied (which
+/// may differ from the requested one, e.g. if it is a [`RampingMotor`]), reversed back into the requested direction.
+/// Reversing a reversed motor results in the original behaviour.
+///
+/// Note that a [`Motor`] can also be reversed with [`Motor::invert_direction`], this is meant for generic code working
+/// with any [`DriveMotor`].
+#[derive(Debug)]
+pub struct ReversedMotor<M> {
+    inner: M,
+    /// The reversed command of the wrapped motor, refreshed after each drive (as it can't be returned by reference
+    /// otherwise).
+    current_drive_command: DriveCommand,
+}
+
+impl<M: DriveMotor> ReversedMotor<M> {
+    /// Wrap the motor, reversing all commands given to it from now on.
+    pub fn new(inner: M) -> ReversedMotor<M> {
+        let current_drive_command = inner.current_drive_command().reverse();
+        ReversedMotor {
+            inner,
+            current_drive_command,
+        }
+    }
+
+    /// Drive the motor with the reversed command.
+    ///
+    /// # Errors
+    /// See the [`DriveMotor::drive`] implementation of the wrapped motor.
+    pub fn drive(&mut self, drive_command: DriveCommand) -> Result<(), M::Error> {
+        let result = self.inner.drive(drive_command.reverse());
+        self.current_drive_command = self.inner.current_drive_command().reverse();
+        result
+    }
+
+    /// Get the currently active drive command of the wrapped motor, reversed back into the requested direction.
+    pub fn current_drive_command(&self) -> &DriveCommand {
+        &self.current_drive_command
+    }
+
+    /// Returns a reference to the wrapped motor.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Release the wrapped motor.
+    pub fn release(self) -> M {
+        self.inner
+    }
+}
+
+impl<M: DriveMotor> DriveMotor for ReversedMotor<M> {
+    type Error = M::Error;
+
+    /// See [`ReversedMotor::drive`].
+    fn drive(&mut self, drive_command: DriveCommand) -> Result<(), Self::Error> {
+        ReversedMotor::drive(self, drive_command)
+    }
+
+    fn current_drive_command(&self) -> &DriveCommand {
+        &self.current_drive_command
+    }
+
+    fn current_speed(&self) -> i8 {
+        -self.inner.current_speed()
+    }
+}
+
+/// An emergency stop path which is distinct from stopping the motors during normal operation.
+///
+/// For a [`Motor`] this actively [brakes](DriveCommand::Brake) it. For a [`Tb6612fng`] this additionally enables
+/// standby (see [`Tb6612fng::emergency_stop`]), which cuts all outputs in hardware instead of relying on the
+/// brake signals generated via the IN pins.
+///
+/// # Safety guidance
+/// This is a software mechanism which relies on working pins and firmware, it is no substitute for a hardware
+/// emergency stop (e.g. a switch interrupting the motor supply) where people or machinery could be harmed. Make sure
+/// to handle the returned error, e.g. by cutting the power through other means, as the motors may still be running if
+/// it failed. Do not drive the motors again (or disable standby) until the cause of the emergency has been resolved.
+pub trait EmergencyStop {
+    /// The error which can happen when stopping.
+    type Error;
+
+    /// Stop all outputs as fast as possible.
+    ///
+    /// # Errors
+    /// See the implementation, e.g. [`Tb6612fng::emergency_stop`].
+    fn emergency_stop(&mut self) -> Result<(), Self::Error>;
+}
+
+impl<IN1, IN2, PWM> EmergencyStop for Motor<IN1, IN2, PWM>
+where
+    IN1: OutputPin,
+    IN2: OutputPin,
+    PWM: SetDutyCycle,
+{
+    type Error = MotorError<IN1::Error, IN2::Error, PWM::Error>;
+
+    /// Actively [brake](DriveCommand::Brake) the motor.
+    fn emergency_stop(&mut self) -> Result<(), Self::Error> {
+        self.drive(DriveCommand::Brake)
+    }
+}
+
+impl<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY> EmergencyStop
+    for Tb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>
+where
+    MAIN1: OutputPin,
+    MAIN2: OutputPin,
+    MAPWM: SetDutyCycle,
+    MBIN1: OutputPin,
+    MBIN2: OutputPin,
+    MBPWM: SetDutyCycle,
+    STBY: OutputPin,
+{
+    type Error = Tb6612fngControlError<
+        MotorError<MAIN1::Error, MAIN2::Error, MAPWM::Error>,
+        MotorError<MBIN1::Error, MBIN2::Error, MBPWM::Error>,
+        STBY::Error,
+    >;
+
+    /// See [`Tb6612fng::emergency_stop`].
+    fn emergency_stop(&mut self) -> Result<(), Self::Error> {
+        Tb6612fng::emergency_stop(self)
+    }
+}
+
+/// A PWM output which can be completely turned on and off, in addition to setting its duty cycle.
+///
+/// `embedded-hal` does not define this, so you need to implement it for your HAL's PWM channel to be able to use
+/// [`Motor::disable`] and [`Motor::enable`].
+pub trait PwmEnable: SetDutyCycle {
+    /// Enable the PWM output.
+    fn enable(&mut self) -> Result<(), Self::Error>;
+
+    /// Disable the PWM output.
+    fn disable(&mut self) -> Result<(), Self::Error>;
+}
+
+/// A PWM peripheral (usually a timer) which offers multiple channels which can be controlled independently.
+///
+/// Implement this for your HAL's timer to be able to share it between both motors using [`DualChannelPwm`].
+pub trait MultiChannelPwm: ErrorType {
+    /// The type used to identify a single channel of the peripheral.
+    type Channel: Copy;
+
+    /// Get the maximum duty cycle value of the given channel.
+    fn max_duty_cycle(&self, channel: Self::Channel) -> u16;
+
+    /// Set the duty cycle of the given channel to `duty / max_duty`.
+    fn set_duty_cycle(&mut self, channel: Self::Channel, duty: u16) -> Result<(), Self::Error>;
+}
+
+/// Adapter which allows sharing a single [`MultiChannelPwm`] peripheral between both motors.
+///
+/// Each channel handle obtained via [`DualChannelPwm::split`] implements [`SetDutyCycle`] and can thus be passed
+/// to [`Motor::new`] or [`Tb6612fng::new`] like any other PWM pin.
+///
+/// # Usage example
+/// ```
+/// # use embedded_hal::pwm::ErrorType;
+/// # use tb6612fng::{DualChannelPwm, MultiChannelPwm};
+/// # #[derive(Debug)]
+/// # struct Timer;
+/// # impl ErrorType for Timer { type Error = core::convert::Infallible; }
+/// # impl MultiChannelPwm for Timer {
+/// #     type Channel = u8;
+/// #     fn max_duty_cycle(&self, _channel: u8) -> u16 { 100 }
+/// #     fn set_duty_cycle(&mut self, _channel: u8, _duty: u16) -> Result<(), Self::Error> { Ok(()) }
+/// # }
+/// let timer = DualChannelPwm::new(Timer);
+/// let (pwm_a, pwm_b) = timer.split(1, 2);
+/// // pass `pwm_a` and `pwm_b` to `Tb6612fng::new` (or to two `Motor`s)
+/// # drop((pwm_a, pwm_b));
+/// let _timer: Timer = timer.release();
+/// ```
+#[derive(Debug)]
+pub struct DualChannelPwm<PWM> {
+    pwm: RefCell<PWM>,
+}
+
+impl<PWM: MultiChannelPwm> DualChannelPwm<PWM> {
+    /// Wrap the given PWM peripheral so that its channels can be shared.
+    pub fn new(pwm: PWM) -> DualChannelPwm<PWM> {
+        DualChannelPwm {
+            pwm: RefCell::new(pwm),
+        }
+    }
+
+    /// Get handles for the two channels which are used by the motors.
+    pub fn split(
+        &self,
+        channel_a: PWM::Channel,
+        channel_b: PWM::Channel,
+    ) -> (PwmChannel<'_, PWM>, PwmChannel<'_, PWM>) {
+        (self.channel(channel_a), self.channel(channel_b))
+    }
+
+    /// Get a handle for a single channel of the peripheral.
+    pub fn channel(&self, channel: PWM::Channel) -> PwmChannel<'_, PWM> {
+        PwmChannel {
+            pwm: &self.pwm,
+            channel,
+        }
+    }
+
+    /// Release the adapter, returning the PWM peripheral so that it can be used for something else.
+    pub fn release(self) -> PWM {
+        self.pwm.into_inner()
+    }
+}
+
+/// A single channel of a PWM peripheral shared via [`DualChannelPwm`].
+#[derive(Debug)]
+pub struct PwmChannel<'a, PWM: MultiChannelPwm> {
+    pwm: &'a RefCell<PWM>,
+    channel: PWM::Channel,
+}
+
+impl<PWM: MultiChannelPwm> ErrorType for PwmChannel<'_, PWM> {
+    type Error = PWM::Error;
+}
+
+impl<PWM: MultiChannelPwm> SetDutyCycle for PwmChannel<'_, PWM> {
+    fn max_duty_cycle(&self) -> u16 {
+        self.pwm.borrow().max_duty_cycle(self.channel)
+    }
+
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        self.pwm.borrow_mut().set_duty_cycle(self.channel, duty)
+    }
+}
+
+/// An async variant of [`Motor`] for async executors like [Embassy](https://embassy.dev), available with the
+/// `async-hal` feature.
+///
+/// `embedded-hal-async` does not define async variants of [`OutputPin`] or [`SetDutyCycle`] (setting a GPIO or a PWM
+/// duty cycle completes immediately), so the pins still use the blocking traits. The methods which wait for a
+/// duration `.await` an async [`DelayNs`](embedded_hal_async::delay::DelayNs) instead of blocking the executor.
+///
+/// # Usage example
+/// ```
+/// # use embedded_hal::digital::OutputPin;
+/// # use embedded_hal::pwm::SetDutyCycle;
+/// use embedded_hal_async::delay::DelayNs;
+/// use tb6612fng::{AsyncMotor, DriveCommand, MotorError};
+///
+/// async fn nudge<IN1: OutputPin, IN2: OutputPin, PWM: SetDutyCycle>(
+///     motor: &mut AsyncMotor<IN1, IN2, PWM>,
+///     delay: &mut impl DelayNs,
+/// ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+///     motor.drive_for(DriveCommand::forward_const(50), 500, delay).await
+/// }
+/// ```
+#[cfg(feature = "async-hal")]
+#[derive(Debug)]
+pub struct AsyncMotor<IN1, IN2, PWM> {
+    motor: Motor<IN1, IN2, PWM>,
+}
+
+#[cfg(feature = "async-hal")]
+impl<IN1, IN2, PWM> AsyncMotor<IN1, IN2, PWM>
+where
+    IN1: OutputPin,
+    IN2: OutputPin,
+    PWM: SetDutyCycle,
+{
+    /// Instantiate a new [`AsyncMotor`] with the defined pins, see [`Motor::new`].
+    ///
+    /// # Errors
+    /// If any of the underlying pin interactions fail these errors will be propagated up.
+    /// The errors are specific to your HAL.
+    #[allow(clippy::type_complexity)]
+    pub fn new(
+        in1: IN1,
+        in2: IN2,
+        pwm: PWM,
+    ) -> Result<AsyncMotor<IN1, IN2, PWM>, MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        Motor::new(in1, in2, pwm).map(AsyncMotor::from)
+    }
+
+    /// Drive with the defined command, see [`Motor::drive`].
+    ///
+    /// Setting the pins completes immediately, thus this never yields to the executor.
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[allow(clippy::type_complexity)]
+    pub async fn drive(
+        &mut self,
+        drive_command: DriveCommand,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        self.motor.drive(drive_command)
+    }
+
+    /// Drive with the defined command for the defined duration (in milliseconds) and then let the motor
+    /// [coast](DriveCommand::Stop), see [`Motor::drive_for`].
+    ///
+    /// Unlike [`Motor::drive_for`] this does not block: other tasks can run while waiting for the `delay`.
+    ///
+    /// # Errors
+    /// See [`Motor::drive_for`].
+    #[allow(clippy::type_complexity)]
+    pub async fn drive_for(
+        &mut self,
+        drive_command: DriveCommand,
+        duration_ms: u32,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        if let Err(e) = self.motor.drive(drive_command) {
+            let _ = self.motor.drive(DriveCommand::Stop);
+            return Err(e);
+        }
+
+        delay.delay_ms(duration_ms).await;
+
+        self.motor.drive(DriveCommand::Stop)
+    }
+
+    /// Drive with the defined command, but actively brake for `brake_ms` milliseconds first if this reverses the
+    /// direction of the motor, see [`Motor::drive_with_reversal_guard`].
+    ///
+    /// # Errors
+    /// See [`Motor::drive`].
+    #[allow(clippy::type_complexity)]
+    pub async fn drive_with_reversal_guard(
+        &mut self,
+        drive_command: DriveCommand,
+        brake_ms: u32,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
+        let (drive_command, is_reversal) = self.motor.check_reversal(drive_command);
+
+        if is_reversal {
+            self.motor.drive(DriveCommand::Brake)?;
+            delay.delay_ms(brake_ms).await;
+        }
+
+        self.motor.drive(drive_command)
+    }
+
+    /// Get the currently active drive command.
+    pub fn current_drive_command(&self) -> &DriveCommand {
+        self.motor.current_drive_command()
+    }
+
+    /// Returns the current speed as a signed value (see [`Motor::current_speed`]).
+    pub fn current_speed(&self) -> i8 {
+        self.motor.current_speed()
+    }
+
+    /// Returns a reference to the wrapped motor.
+    pub fn inner(&self) -> &Motor<IN1, IN2, PWM> {
+        &self.motor
+    }
+
+    /// Returns a mutable reference to the wrapped motor, e.g. to configure it or to use any of its non-blocking
+    /// methods.
+    pub fn inner_mut(&mut self) -> &mut Motor<IN1, IN2, PWM> {
+        &mut self.motor
+    }
+
+    /// Release the wrapped motor.
+    pub fn release(self) -> Motor<IN1, IN2, PWM> {
+        self.motor
+    }
+}
+
+#[cfg(feature = "async-hal")]
+impl<IN1, IN2, PWM> From<Motor<IN1, IN2, PWM>> for AsyncMotor<IN1, IN2, PWM> {
+    /// Use the motor from async tasks, e.g. after it has been created with any of the constructors of [`Motor`].
+    fn from(motor: Motor<IN1, IN2, PWM>) -> Self {
+        AsyncMotor { motor }
+    }
+}
+
+/// An async variant of [`Tb6612fng`] which controls two [`AsyncMotor`]s, available with the `async-hal` feature.
+///
+/// Like [`AsyncMotor`] this uses the blocking [`OutputPin`] trait for the standby pin as `embedded-hal-async` does not
+/// define an async variant of it, the standby methods are `async` so that they can be `.await`ed alongside the
+/// methods of the motors.
+#[cfg(feature = "async-hal")]
+#[derive(Debug)]
+pub struct AsyncTb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY> {
+    /// The first motor, labelled as 'A' on the chip
+    pub motor_a: AsyncMotor<MAIN1, MAIN2, MAPWM>,
+    /// The second motor, labelled as 'B' on the chip
+    pub motor_b: AsyncMotor<MBIN1, MBIN2, MBPWM>,
+    /// The standby pin used to put both motors on standby
+    standby: STBY,
+    /// Whether standby has last been enabled or disabled
+    standby_enabled: bool,
+}
+
+#[cfg(feature = "async-hal")]
+impl<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>
+    AsyncTb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>
+where
+    MAIN1: OutputPin,
+    MAIN2: OutputPin,
+    MAPWM: SetDutyCycle,
+    MBIN1: OutputPin,
+    MBIN2: OutputPin,
+    MBPWM: SetDutyCycle,
+    STBY: OutputPin,
+{
+    /// Instantiate a new [`AsyncTb6612fng`] with the defined motors and standby pin, see [`Tb6612fng::new`].
+    /// The initial state of standby will be *disabled*.
+    ///
+    /// # Errors
+    /// If the underlying pin interaction fails this error will be propagated up.
+    /// The error is specific to your HAL.
+    #[allow(clippy::type_complexity)]
+    pub fn new(
+        motor_a: AsyncMotor<MAIN1, MAIN2, MAPWM>,
+        motor_b: AsyncMotor<MBIN1, MBIN2, MBPWM>,
+        mut standby: STBY,
+    ) -> Result<
+        AsyncTb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>,
+        Tb6612fngError<STBY::Error>,
+    > {
+        standby.set_high().map_err(Tb6612fngError::Standby)?;
+
+        Ok(AsyncTb6612fng {
+            motor_a,
+            motor_b,
+            standby,
+            standby_enabled: false,
+        })
+    }
+
+    /// Drive both motors with the defined commands, first motor A and then motor B, see [`Tb6612fng::drive`].
+    ///
+    /// # Errors
+    /// See [`Tb6612fng::drive`].
+    #[allow(clippy::type_complexity)]
+    pub async fn drive(
+        &mut self,
+        drive_command_a: DriveCommand,
+        drive_command_b: DriveCommand,
+    ) -> Result<
+        (),
+        Tb6612fngDriveError<
+            MotorError<MAIN1::Error, MAIN2::Error, MAPWM::Error>,
+            MotorError<MBIN1::Error, MBIN2::Error, MBPWM::Error>,
+        >,
+    > {
+        self.motor_a
+            .drive(drive_command_a)
+            .await
+            .map_err(Tb6612fngDriveError::MotorA)?;
+        self.motor_b
+            .drive(drive_command_b)
+            .await
+            .map_err(Tb6612fngDriveError::MotorB)?;
+
+        Ok(())
+    }
+
+    /// Enable standby, see [`Tb6612fng::enable_standby`].
+    ///
+    /// # Errors
+    /// If the underlying pin interaction fails this error will be propagated up.
+    /// The error is specific to your HAL.
+    pub async fn enable_standby(&mut self) -> Result<(), STBY::Error> {
+        log_debug!(
+            "enabling standby (motor A: {}, motor B: {})",
+            self.motor_a.current_drive_command(),
+            self.motor_b.current_drive_command()
+        );
+        self.standby.set_low()?;
+        self.standby_enabled = true;
+        Ok(())
+    }
+
+    /// Disable standby, see [`Tb6612fng::disable_standby`]. Note that the last active commands on the motors will
+    /// resume.
+    ///
+    /// # Errors
+    /// If the underlying pin interaction fails this error will be propagated up.
+    /// The error is specific to your HAL.
+    pub async fn disable_standby(&mut self) -> Result<(), STBY::Error> {
+        log_debug!(
+            "disabling standby, resuming motor A: {}, motor B: {}",
+            self.motor_a.current_drive_command(),
+            self.motor_b.current_drive_command()
+        );
+        self.standby.set_high()?;
+        self.standby_enabled = false;
+        Ok(())
+    }
+
+    /// Returns whether the standby mode is enabled, see [`Tb6612fng::standby_enabled`].
+    pub fn standby_enabled(&self) -> bool {
+        self.standby_enabled
+    }
+
+    /// Release the motors and the standby pin.
+    #[allow(clippy::type_complexity)]
+    pub fn release(
+        self,
+    ) -> (
+        AsyncMotor<MAIN1, MAIN2, MAPWM>,
+        AsyncMotor<MBIN1, MBIN2, MBPWM>,
+        STBY,
+    ) {
+        (self.motor_a, self.motor_b, self.standby)
+    }
+}
+
+#[cfg(feature = "async-hal")]
+impl<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>
+    From<Tb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>>
+    for AsyncTb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>
+{
+    /// Use the controller from async tasks, keeping its standby state.
+    fn from(controller: Tb6612fng<MAIN1, MAIN2, MAPWM, MBIN1, MBIN2, MBPWM, STBY>) -> Self {
+        AsyncTb6612fng {
+            motor_a: AsyncMotor::from(controller.motor_a),
+            motor_b: AsyncMotor::from(controller.motor_b),
+            standby: controller.standby,
+            standby_enabled: controller.standby_enabled,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    #[cfg(feature = "async-hal")]
+    use crate::{AsyncMotor, AsyncTb6612fng};
+    use crate::{
+        BoundedSpeed, ClosedLoopError, ClosedLoopGains, ControllerSnapshot, DeadbandMotor,
+        DifferentialDrive, Direction, DriveCommand, DriveMotor, DualChannelPwm, EmergencyStop,
+        InvalidSpeedError, Motor, MotorArray, MotorArrayDriveError, MotorError, MotorState,
+        MultiChannelPwm, Percent, PwmEnable, RampingMotor, ReversedMotor, Sequence, Speed,
+        SpeedFeedback, Tb6612fng, Tb6612fngControlError, Tb6612fngDriveError, Tb6612fngError,
+    };
+    use embedded_hal::digital::OutputPin;
+    use embedded_hal::pwm::SetDutyCycle;
+    use embedded_hal_mock::eh1::delay::CheckedDelay;
+    use embedded_hal_mock::eh1::delay::Transaction as DelayTransaction;
+    use embedded_hal_mock::eh1::digital::Mock as PinMock;
+    use embedded_hal_mock::eh1::digital::State::{High, Low};
+    use embedded_hal_mock::eh1::digital::Transaction as PinTransaction;
+    use embedded_hal_mock::eh1::pwm::Mock as PwmMock;
+    use embedded_hal_mock::eh1::pwm::Transaction as PwmTransaction;
+    use embedded_hal_mock::eh1::MockError;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn test_motor_stop() {
+        let max_duty = 100;
+        let motor_in1_expectations = [PinTransaction::set(Low)];
+        let motor_in2_expectations = [PinTransaction::set(Low)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(0),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+
+        motor.drive(DriveCommand::Stop).unwrap();
+
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+        assert_eq!(motor.current_speed(), 0);
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_brake() {
+        let max_duty = 100;
+        let motor_in1_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_in2_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(0),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+
+        motor.drive(DriveCommand::Brake).unwrap();
+
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Brake);
+        assert_eq!(motor.current_speed(), 0);
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_drive_forward() {
+        let max_duty = 100;
+        let speed: u8 = 100;
+        let motor_in1_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_in2_expectations = [PinTransaction::set(Low)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(speed as u16),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+
+        motor
+            .drive(DriveCommand::forward_const(speed))
+            .expect("speed can be set");
+
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(100)
+        );
+        assert_eq!(motor.current_speed(), speed as i8);
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_drive_backward() {
+        let max_duty = 100;
+        let speed = 100;
+        let motor_in1_expectations = [PinTransaction::set(Low)];
+        let motor_in2_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(speed as u16),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+
+        motor
+            .drive(DriveCommand::backward_const(speed))
+            .expect("speed can be set");
+
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(100)
+        );
+        assert_eq!(motor.current_speed(), -(speed as i8));
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_drive_invalid_speed() {
+        let max_duty = 100;
+        let motor_in1_expectations = [PinTransaction::set(Low)];
+        let motor_in2_expectations = [PinTransaction::set(Low)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+
+        let current_drive_command = *motor.current_drive_command();
+        let current_speed = motor.current_speed();
+
+        // a `DriveCommand` can't hold an invalid speed, but a raw signed speed can be out of range
+        assert_eq!(
+            motor
+                .drive_signed(101)
+                .expect_err("Invalid speed must result in an exception"),
+            MotorError::InvalidSpeed
+        );
+
+        // this should still be what was set before the invalid command
+        assert_eq!(*motor.current_drive_command(), current_drive_command);
+        assert_eq!(motor.current_speed(), current_speed);
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_drive_forward_shorthand() {
+        let max_duty = 100;
+        let speed: u8 = 50;
+        let motor_in1_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_in2_expectations = [PinTransaction::set(Low)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(speed as u16),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+
+        motor
+            .drive_forward(Percent::new_const(speed))
+            .expect("speed can be set");
+
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(speed)
+        );
+        assert_eq!(motor.current_speed(), speed as i8);
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_drive_backward_shorthand() {
+        let max_duty = 100;
+        let speed: u8 = 50;
+        let motor_in1_expectations = [PinTransaction::set(Low)];
+        let motor_in2_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(speed as u16),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+
+        motor
+            .drive_backward(Percent::new_const(speed))
+            .expect("speed can be set");
+
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(speed)
+        );
+        assert_eq!(motor.current_speed(), -(speed as i8));
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_brake_shorthand() {
+        let max_duty = 100;
+        let motor_in1_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_in2_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(0),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+
+        motor.brake().unwrap();
+
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Brake);
+        assert_eq!(motor.current_speed(), 0);
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_coast_shorthand() {
+        let max_duty = 100;
+        let motor_in1_expectations = [
+            PinTransaction::set(Low),
+            PinTransaction::set(High),
+            PinTransaction::set(Low),
+        ];
+        let motor_in2_expectations = [PinTransaction::set(Low)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(50),
+            PwmTransaction::set_duty_cycle(0),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+
+        motor.drive_forward(Percent::new_const(50)).unwrap();
+        motor.coast().unwrap();
+
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+        assert_eq!(motor.current_speed(), 0);
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_drive_inverted_direction() {
+        let max_duty = 100;
+        let speed: u8 = 75;
+        let motor_in1_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_in2_expectations = [
+            PinTransaction::set(Low),
+            PinTransaction::set(High),
+            PinTransaction::set(Low),
+        ];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(speed as u16),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(speed as u16),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+        assert!(!motor.is_direction_inverted());
+
+        motor.invert_direction(true);
+        assert!(motor.is_direction_inverted());
+
+        motor.drive(DriveCommand::forward_const(speed)).unwrap();
+        // the logical direction is reported, not the physical one
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(speed)
+        );
+        assert_eq!(motor.current_speed(), speed as i8);
+
+        motor.drive(DriveCommand::backward_const(speed)).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(speed)
+        );
+        assert_eq!(motor.current_speed(), -(speed as i8));
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    /// Creates a motor with the defined drive command and pin expectations, skipping the initialisation of [`Motor::new`].
+    fn mock_motor(
+        in1_expectations: &[PinTransaction],
+        in2_expectations: &[PinTransaction],
+        pwm_expectations: &[PwmTransaction],
+        drive_command: DriveCommand,
+    ) -> Motor<PinMock, PinMock, PwmMock> {
+        Motor {
+            in1: PinMock::new(in1_expectations),
+            in2: PinMock::new(in2_expectations),
+            pwm: PwmMock::new(pwm_expectations),
+            max_duty: 100,
+            max_speed: 100,
+            deadband: 0,
+            current_duty: 0,
+            current_drive_command: drive_command,
+            last_failed_drive_command: None,
+            direction_inverted: false,
+            on_drive: None,
+            closed_loop_gains: ClosedLoopGains::default(),
+            closed_loop_integral: 0.0,
+            pwm_frequency: None,
+            last_direction: None,
+            reversal_count: 0,
+            pin_states: (None, None),
+        }
+    }
+
+    /// Creates a motor with the defined drive command without any pin interactions.
+    fn motor_with_drive_command(drive_command: DriveCommand) -> Motor<PinMock, PinMock, PwmMock> {
+        mock_motor(&[], &[], &[], drive_command)
+    }
+
+    fn motor_done(motor: &mut Motor<PinMock, PinMock, PwmMock>) {
+        motor.in1.done();
+        motor.in2.done();
+        motor.pwm.done();
+    }
+
+    /// Creates a controller with the defined motors, standby pin expectations and standby state, skipping the
+    /// initialisation of [`Tb6612fng::new`].
+    fn mock_controller(
+        motor_a: Motor<PinMock, PinMock, PwmMock>,
+        motor_b: Motor<PinMock, PinMock, PwmMock>,
+        standby_expectations: &[PinTransaction],
+        standby_enabled: bool,
+    ) -> Tb6612fng<PinMock, PinMock, PwmMock, PinMock, PinMock, PwmMock, PinMock> {
+        Tb6612fng {
+            motor_a,
+            motor_b,
+            standby: PinMock::new(standby_expectations),
+            standby_enabled,
+        }
+    }
+
+    fn controller_done(
+        mut controller: Tb6612fng<PinMock, PinMock, PwmMock, PinMock, PinMock, PwmMock, PinMock>,
+    ) {
+        motor_done(&mut controller.motor_a);
+        motor_done(&mut controller.motor_b);
+        controller.standby.done();
+    }
+
+    #[test]
+    fn test_motor_state_predicates() {
+        // (drive command, is_moving, is_braking, is_stopped, is_coasting)
+        let cases = [
+            (DriveCommand::forward_const(50), true, false, false, false),
+            (DriveCommand::forward_const(0), false, false, true, false),
+            (DriveCommand::backward_const(50), true, false, false, false),
+            (DriveCommand::backward_const(0), false, false, true, false),
+            (DriveCommand::Brake, false, true, false, false),
+            (DriveCommand::Stop, false, false, true, true),
+        ];
+
+        for (drive_command, is_moving, is_braking, is_stopped, is_coasting) in cases {
+            let mut motor = motor_with_drive_command(drive_command);
+
+            assert_eq!(motor.is_moving(), is_moving, "{:?}", drive_command);
+            assert_eq!(motor.is_braking(), is_braking, "{:?}", drive_command);
+            assert_eq!(motor.is_stopped(), is_stopped, "{:?}", drive_command);
+            assert_eq!(motor.is_coasting(), is_coasting, "{:?}", drive_command);
+
+            motor_done(&mut motor);
+        }
+    }
+
+    #[test]
+    fn test_motor_drive_raw() {
+        let max_duty = 65535;
+        let duty = 1000;
+        let motor_in1_expectations = [PinTransaction::set(Low)];
+        let motor_in2_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(duty),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+
+        motor
+            .drive_raw(Direction::Backward, duty)
+            .expect("duty can be set");
+
+        // 1000 / 65535 = 1.5% => rounded to 2%
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(2)
+        );
+        assert_eq!(motor.current_speed(), -2);
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_drive_raw_invalid_duty() {
+        let max_duty = 1000;
+        let motor_in1_expectations = [PinTransaction::set(Low)];
+        let motor_in2_expectations = [PinTransaction::set(Low)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+
+        assert_eq!(
+            motor
+                .drive_raw(Direction::Forward, max_duty + 1)
+                .expect_err("Invalid duty must result in an exception"),
+            MotorError::InvalidSpeed
+        );
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_reverse() {
+        let max_duty = 100;
+        let speed: u8 = 100;
+        let motor_in1_expectations = [
+            PinTransaction::set(Low),
+            PinTransaction::set(High),
+            PinTransaction::set(Low),
+            PinTransaction::set(High),
+        ];
+        let motor_in2_expectations = [
+            PinTransaction::set(Low),
+            PinTransaction::set(High),
+            PinTransaction::set(Low),
+        ];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(speed as u16),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(speed as u16),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(speed as u16),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+
+        motor.drive(DriveCommand::forward_const(speed)).unwrap();
+
+        motor.reverse().unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(speed)
+        );
+
+        motor.reverse().unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(speed)
+        );
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_reverse_zero_speed() {
+        let max_duty = 100;
+        let motor_in1_expectations = [
+            PinTransaction::set(Low),
+            PinTransaction::set(High),
+            PinTransaction::set(Low),
+        ];
+        let motor_in2_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(0),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+
+        motor.drive(DriveCommand::forward_const(0)).unwrap();
+
+        motor.reverse().unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(0)
+        );
+        assert_eq!(motor.current_speed(), 0);
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_ramp_to() {
+        let max_duty = 100;
+        let motor_in1_expectations = [
+            PinTransaction::set(Low),
+            PinTransaction::set(High),
+            PinTransaction::set(Low),
+        ];
+        let motor_in2_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(10),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(10),
+            PwmTransaction::set_duty_cycle(15),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+
+        motor.drive(DriveCommand::forward_const(10)).unwrap();
+
+        let target = DriveCommand::backward_const(15);
+        assert!(!motor.ramp_to(target, 10).unwrap());
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+        assert!(!motor.ramp_to(target, 10).unwrap());
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(10)
+        );
+        assert!(motor.ramp_to(target, 10).unwrap());
+        assert_eq!(*motor.current_drive_command(), target);
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_drive_signed() {
+        let max_duty = 100;
+        let motor_in1_expectations = [
+            PinTransaction::set(Low),
+            PinTransaction::set(High),
+            PinTransaction::set(Low),
+        ];
+        let motor_in2_expectations = [
+            PinTransaction::set(Low),
+            PinTransaction::set(High),
+            PinTransaction::set(Low),
+        ];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(50),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(100),
+            PwmTransaction::set_duty_cycle(0),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+
+        motor.drive_signed(-50).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(50)
+        );
+
+        motor.drive_signed(100).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(100)
+        );
+
+        motor.drive_signed(0).unwrap();
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_drive_signed_invalid_speed() {
+        let mut motor = motor_with_drive_command(DriveCommand::Stop);
+
+        for speed in [101, -101, i8::MIN, i8::MAX] {
+            assert_eq!(
+                motor
+                    .drive_signed(speed)
+                    .expect_err("Invalid speed must result in an exception"),
+                MotorError::InvalidSpeed
+            );
+        }
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_motor_release() {
+        let max_duty = 100;
+        let motor_in1_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_in2_expectations = [PinTransaction::set(Low), PinTransaction::set(Low)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(42),
+        ];
+        let motor_in1 = PinMock::new(&motor_in1_expectations);
+        let motor_in2 = PinMock::new(&motor_in2_expectations);
+        let motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let motor = Motor::new(motor_in1, motor_in2, motor_pwm).unwrap();
+
+        let (mut motor_in1, mut motor_in2, mut motor_pwm) = motor.release();
+
+        // the released pins can be used directly
+        motor_in1.set_high().unwrap();
+        motor_in2.set_low().unwrap();
+        motor_pwm.set_duty_cycle(42).unwrap();
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_tb6612fng_release() {
+        let max_duty = 100;
+        let motor_a_in1 = PinMock::new(&[PinTransaction::set(Low)]);
+        let motor_a_in2 = PinMock::new(&[PinTransaction::set(Low)]);
+        let motor_a_pwm = PwmMock::new(&[
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+        ]);
+        let motor_b_in1 = PinMock::new(&[PinTransaction::set(Low)]);
+        let motor_b_in2 = PinMock::new(&[PinTransaction::set(Low)]);
+        let motor_b_pwm = PwmMock::new(&[
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+        ]);
+        let standby = PinMock::new(&[PinTransaction::set(High), PinTransaction::set(Low)]);
+
+        let controller = Tb6612fng::new(
+            Motor::new(motor_a_in1, motor_a_in2, motor_a_pwm).unwrap(),
+            Motor::new(motor_b_in1, motor_b_in2, motor_b_pwm).unwrap(),
+            standby,
+        )
+        .unwrap();
+
+        let (motor_a, motor_b, mut standby) = controller.release();
+
+        // the released standby pin can be used directly
+        standby.set_low().unwrap();
+
+        let (mut motor_a_in1, mut motor_a_in2, mut motor_a_pwm) = motor_a.release();
+        let (mut motor_b_in1, mut motor_b_in2, mut motor_b_pwm) = motor_b.release();
+
+        motor_a_in1.done();
+        motor_a_in2.done();
+        motor_a_pwm.done();
+        motor_b_in1.done();
+        motor_b_in2.done();
+        motor_b_pwm.done();
+        standby.done();
+    }
+
+    #[test]
+    fn test_motor_increment_decrement_speed() {
+        let max_duty = 100;
+        let motor_in1_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_in2_expectations = [PinTransaction::set(Low)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(90),
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(100),
+            PwmTransaction::set_duty_cycle(50),
+            PwmTransaction::set_duty_cycle(0),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+
+        motor.drive(DriveCommand::forward_const(90)).unwrap();
+
+        // saturates at 100%
+        motor.increment_speed(20).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(100)
+        );
+
+        motor.decrement_speed(50).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(50)
+        );
+
+        // saturates at 0%
+        motor.decrement_speed(255).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(0)
+        );
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_increment_decrement_speed_noop() {
+        for drive_command in [DriveCommand::Brake, DriveCommand::Stop] {
+            let mut motor = motor_with_drive_command(drive_command);
+
+            motor.increment_speed(10).unwrap();
+            assert_eq!(*motor.current_drive_command(), drive_command);
+            motor.decrement_speed(10).unwrap();
+            assert_eq!(*motor.current_drive_command(), drive_command);
+
+            motor_done(&mut motor);
+        }
+    }
+
+    #[test]
+    fn test_tb6612fng_drive() {
+        let max_duty = 100;
+        let motor_a_in1 = PinMock::new(&[PinTransaction::set(Low), PinTransaction::set(High)]);
+        let motor_a_in2 = PinMock::new(&[PinTransaction::set(Low)]);
+        let motor_a_pwm = PwmMock::new(&[
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(50),
+        ]);
+        let motor_b_in1 = PinMock::new(&[PinTransaction::set(Low)]);
+        let motor_b_in2 = PinMock::new(&[PinTransaction::set(Low), PinTransaction::set(High)]);
+        let motor_b_pwm = PwmMock::new(&[
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(75),
+        ]);
+        let standby = PinMock::new(&[PinTransaction::set(High)]);
+
+        let mut controller = Tb6612fng::new(
+            Motor::new(motor_a_in1, motor_a_in2, motor_a_pwm).unwrap(),
+            Motor::new(motor_b_in1, motor_b_in2, motor_b_pwm).unwrap(),
+            standby,
+        )
+        .unwrap();
+
+        controller
+            .drive(
+                DriveCommand::forward_const(50),
+                DriveCommand::backward_const(75),
+            )
+            .unwrap();
+
+        assert_eq!(
+            *controller.motor_a.current_drive_command(),
+            DriveCommand::forward_const(50)
+        );
+        assert_eq!(
+            *controller.motor_b.current_drive_command(),
+            DriveCommand::backward_const(75)
+        );
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_tb6612fng_drive_error() {
+        let max_duty = 100;
+        let motor_a_in1 = PinMock::new(&[
+            PinTransaction::set(Low),
+            PinTransaction::set(High).with_error(MockError::Io(ErrorKind::NotConnected)),
+            PinTransaction::set(High),
+        ]);
+        let motor_a_in2 = PinMock::new(&[PinTransaction::set(Low), PinTransaction::set(High)]);
+        let motor_a_pwm = PwmMock::new(&[
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(0),
+        ]);
+        let motor_b_in1 = PinMock::new(&[PinTransaction::set(Low)]);
+        let motor_b_in2 = PinMock::new(&[
+            PinTransaction::set(Low),
+            PinTransaction::set(High).with_error(MockError::Io(ErrorKind::NotConnected)),
+        ]);
+        let motor_b_pwm = PwmMock::new(&[
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+        ]);
+        let standby = PinMock::new(&[PinTransaction::set(High)]);
+
+        let mut controller = Tb6612fng::new(
+            Motor::new(motor_a_in1, motor_a_in2, motor_a_pwm).unwrap(),
+            Motor::new(motor_b_in1, motor_b_in2, motor_b_pwm).unwrap(),
+            standby,
+        )
+        .unwrap();
+
+        assert_eq!(
+            controller
+                .drive(
+                    DriveCommand::forward_const(50),
+                    DriveCommand::forward_const(50)
+                )
+                .expect_err("Pin error must result in an exception"),
+            Tb6612fngDriveError::MotorA(MotorError::In1Error(MockError::Io(
+                ErrorKind::NotConnected
+            )))
+        );
+        // motor B is not driven if motor A fails
+        assert_eq!(
+            *controller.motor_b.current_drive_command(),
+            DriveCommand::Stop
+        );
+
+        assert_eq!(
+            controller
+                .drive(DriveCommand::Brake, DriveCommand::backward_const(100))
+                .expect_err("Pin error must result in an exception"),
+            Tb6612fngDriveError::MotorB(MotorError::In2Error(MockError::Io(
+                ErrorKind::NotConnected
+            )))
+        );
+        assert_eq!(
+            *controller.motor_a.current_drive_command(),
+            DriveCommand::Brake
+        );
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_motor_drive_with_reversal_guard() {
+        let max_duty = 100;
+        let motor_in1_expectations = [
+            PinTransaction::set(Low),
+            PinTransaction::set(High),
+            PinTransaction::set(Low),
+        ];
+        let motor_in2_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(100),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(100),
+            PwmTransaction::set_duty_cycle(50),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+
+        motor.drive(DriveCommand::forward_const(100)).unwrap();
+
+        // reversing the direction brakes first
+        let mut waited = 0;
+        motor
+            .drive_with_reversal_guard(DriveCommand::backward_const(100), || waited += 1)
+            .unwrap();
+        assert_eq!(waited, 1);
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(100)
+        );
+
+        // keeping the direction doesn't brake
+        motor
+            .drive_with_reversal_guard(DriveCommand::backward_const(50), || waited += 1)
+            .unwrap();
+        assert_eq!(waited, 1);
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(50)
+        );
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_drive_with_reversal_guard_deadband() {
+        let mut motor = mock_motor(
+            &[PinTransaction::set(Low)],
+            &[PinTransaction::set(Low)],
+            &[PwmTransaction::set_duty_cycle(0)],
+            DriveCommand::forward_const(50),
+        );
+        motor.set_deadband(20);
+
+        // within the deadband this just stops the motor, there is nothing to reverse
+        motor
+            .drive_with_reversal_guard(DriveCommand::backward_const(10), || {
+                panic!("must not brake for a command within the deadband")
+            })
+            .unwrap();
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_motor_drive_if_changed() {
+        let motor_in1_expectations = [PinTransaction::set(High)];
+        let motor_in2_expectations = [PinTransaction::set(Low)];
+        let motor_pwm_expectations = [PwmTransaction::set_duty_cycle(75)];
+        let mut motor = mock_motor(
+            &motor_in1_expectations,
+            &motor_in2_expectations,
+            &motor_pwm_expectations,
+            DriveCommand::forward_const(50),
+        );
+
+        // unchanged => no pin interactions at all
+        assert!(!motor
+            .drive_if_changed(DriveCommand::forward_const(50))
+            .unwrap());
+
+        // changed speed => the command is applied
+        assert!(motor
+            .drive_if_changed(DriveCommand::forward_const(75))
+            .unwrap());
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(75)
+        );
+
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_motor_drive_if_changed_deadband() {
+        let motor_in1_expectations = [PinTransaction::set(High), PinTransaction::set(Low)];
+        let motor_in2_expectations = [PinTransaction::set(Low)];
+        let motor_pwm_expectations = [
+            PwmTransaction::set_duty_cycle(30),
+            PwmTransaction::set_duty_cycle(0),
+        ];
+        let mut motor = mock_motor(
+            &motor_in1_expectations,
+            &motor_in2_expectations,
+            &motor_pwm_expectations,
+            DriveCommand::Stop,
+        );
+        motor.set_deadband(20);
+
+        // within the deadband => already stopped
+        assert!(!motor
+            .drive_if_changed(DriveCommand::forward_const(10))
+            .unwrap());
+
+        assert!(motor
+            .drive_if_changed(DriveCommand::forward_const(30))
+            .unwrap());
+        assert!(motor
+            .drive_if_changed(DriveCommand::forward_const(10))
+            .unwrap());
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+        assert!(!motor
+            .drive_if_changed(DriveCommand::backward_const(10))
+            .unwrap());
+
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_motor_new_with_initial_command() {
+        let max_duty = 100;
+        let motor_in1_expectations = [PinTransaction::set(High)];
+        let motor_in2_expectations = [PinTransaction::set(High)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let motor = Motor::new_with_initial_command(
+            motor_in1.clone(),
+            motor_in2.clone(),
+            motor_pwm.clone(),
+            DriveCommand::Brake,
+        )
+        .unwrap();
+
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Brake);
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_drive_skips_unchanged_pins() {
+        let max_duty = 100;
+        let motor_in1_expectations = [
+            PinTransaction::set(Low),
+            PinTransaction::set(High),
+            PinTransaction::set(Low),
+            PinTransaction::set(High).with_error(MockError::Io(ErrorKind::NotConnected)),
+            PinTransaction::set(High),
+        ];
+        let motor_in2_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(50),
+            PwmTransaction::set_duty_cycle(75),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(30),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(0),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+
+        // only the PWM changes when keeping the direction
+        motor.drive(DriveCommand::forward_const(50)).unwrap();
+        motor.drive(DriveCommand::forward_const(75)).unwrap();
+        // changing the direction sets both pins
+        motor.drive(DriveCommand::backward_const(30)).unwrap();
+
+        // the PWM is switched off before the pins change; IN2 is already high and the failed IN1 write is repeated
+        // on the next drive
+        assert_eq!(
+            motor.drive(DriveCommand::Brake),
+            Err(MotorError::In1Error(MockError::Io(ErrorKind::NotConnected)))
+        );
+        motor.drive(DriveCommand::Brake).unwrap();
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Brake);
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    /// The pin interactions of a motor, recorded in the order in which they happened across all pins.
+    type OutputLog = std::rc::Rc<core::cell::RefCell<std::vec::Vec<(&'static str, u16)>>>;
+
+    /// A pin which records its outputs in a log shared with other pins (`1` meaning high for output pins).
+    #[derive(Debug)]
+    struct LoggingPin {
+        name: &'static str,
+        max_duty: u16,
+        log: OutputLog,
+    }
+
+    impl embedded_hal::digital::ErrorType for LoggingPin {
+        type Error = core::convert::Infallible;
+    }
+
+    impl OutputPin for LoggingPin {
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            self.log.borrow_mut().push((self.name, 0));
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            self.log.borrow_mut().push((self.name, 1));
+            Ok(())
+        }
+    }
+
+    impl embedded_hal::pwm::ErrorType for LoggingPin {
+        type Error = core::convert::Infallible;
+    }
+
+    impl SetDutyCycle for LoggingPin {
+        fn max_duty_cycle(&self) -> u16 {
+            self.max_duty
+        }
+
+        fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+            self.log.borrow_mut().push((self.name, duty));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_motor_drive_switches_pwm_off_while_changing_direction() {
+        let log = OutputLog::default();
+        let pin = |name| LoggingPin {
+            name,
+            max_duty: 100,
+            log: log.clone(),
+        };
+
+        let mut motor = Motor::new(pin("in1"), pin("in2"), pin("pwm")).unwrap();
+        motor.drive(DriveCommand::forward_const(50)).unwrap();
+        log.borrow_mut().clear();
+
+        motor.drive(DriveCommand::backward_const(30)).unwrap();
+        assert_eq!(
+            *log.borrow(),
+            [("pwm", 0), ("in1", 0), ("in2", 1), ("pwm", 30)]
+        );
+        log.borrow_mut().clear();
+
+        // the PWM is only written once when it has to be off anyway
+        motor.drive(DriveCommand::Brake).unwrap();
+        assert_eq!(*log.borrow(), [("pwm", 0), ("in1", 1)]);
+        log.borrow_mut().clear();
+
+        // the PWM is already off
+        motor.drive(DriveCommand::forward_const(80)).unwrap();
+        assert_eq!(*log.borrow(), [("in2", 0), ("pwm", 80)]);
+        log.borrow_mut().clear();
+
+        // no direction change
+        motor.drive(DriveCommand::forward_const(40)).unwrap();
+        assert_eq!(*log.borrow(), [("pwm", 40)]);
+    }
+
+    #[test]
+    fn test_motor_last_failed_drive_command() {
+        let max_duty = 100;
+        let motor_in1_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_in2_expectations = [PinTransaction::set(Low)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(10),
+            PwmTransaction::set_duty_cycle(20).with_error(MockError::Io(ErrorKind::NotConnected)),
+            PwmTransaction::set_duty_cycle(20),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+        assert_eq!(motor.last_failed_drive_command(), None);
+
+        motor.drive(DriveCommand::forward_const(10)).unwrap();
+
+        assert_eq!(
+            motor.increment_speed(10),
+            Err(MotorError::PwmError(MockError::Io(ErrorKind::NotConnected)))
+        );
+        assert_eq!(
+            motor.last_failed_drive_command(),
+            Some(DriveCommand::forward_const(20))
+        );
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(10)
+        );
+
+        // retry the failed command
+        motor
+            .drive(motor.last_failed_drive_command().unwrap())
+            .unwrap();
+        assert_eq!(motor.last_failed_drive_command(), None);
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(20)
+        );
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_apply() {
+        let motor_in1_expectations = [PinTransaction::set(Low)];
+        let motor_in2_expectations = [PinTransaction::set(High)];
+        let motor_pwm_expectations = [PwmTransaction::set_duty_cycle(55)];
+        let mut motor = mock_motor(
+            &motor_in1_expectations,
+            &motor_in2_expectations,
+            &motor_pwm_expectations,
+            DriveCommand::backward_const(50),
+        );
+
+        motor
+            .apply(|drive_command| {
+                assert_eq!(drive_command, DriveCommand::backward_const(50));
+                DriveCommand::backward_const(55)
+            })
+            .unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(55)
+        );
+
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_motor_new_unchecked() {
+        let max_duty = 100;
+        let motor_in1_expectations = [PinTransaction::set(Low)];
+        let motor_in2_expectations = [PinTransaction::set(Low)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let mut motor =
+            Motor::new_unchecked(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone());
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+
+        // the pins are only touched upon initialisation
+        motor.init().unwrap();
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_drive_command_from_signed_speed() {
+        assert_eq!(
+            DriveCommand::from_signed_speed(75),
+            Ok(DriveCommand::forward_const(75))
+        );
+        assert_eq!(
+            DriveCommand::from_signed_speed(100),
+            Ok(DriveCommand::forward_const(100))
+        );
+        assert_eq!(
+            DriveCommand::from_signed_speed(-50),
+            Ok(DriveCommand::backward_const(50))
+        );
+        assert_eq!(
+            DriveCommand::from_signed_speed(-100),
+            Ok(DriveCommand::backward_const(100))
+        );
+        assert_eq!(DriveCommand::from_signed_speed(0), Ok(DriveCommand::Stop));
+
+        assert_eq!(DriveCommand::from_signed_speed(101), Err(InvalidSpeedError));
+        assert_eq!(
+            DriveCommand::from_signed_speed(-101),
+            Err(InvalidSpeedError)
+        );
+        assert_eq!(
+            DriveCommand::from_signed_speed(i8::MIN),
+            Err(InvalidSpeedError)
+        );
+
+        assert_eq!(
+            DriveCommand::try_from(-50i8),
+            Ok(DriveCommand::backward_const(50))
+        );
+        assert_eq!(DriveCommand::try_from(i8::MIN), Err(InvalidSpeedError));
+    }
+
+    #[test]
+    fn test_motor_duty_for_percent() {
+        let mut motor = motor_with_drive_command(DriveCommand::Stop);
+
+        assert_eq!(motor.duty_for_percent(0), 0);
+        assert_eq!(motor.duty_for_percent(42), 42);
+        assert_eq!(motor.duty_for_percent(100), 100);
+
+        // low resolution PWM
+        motor.max_duty = 50;
+        assert_eq!(motor.duty_for_percent(1), 0);
+        assert_eq!(motor.duty_for_percent(2), 1);
+        assert_eq!(motor.duty_for_percent(3), 1);
+        assert_eq!(motor.duty_for_percent(100), 50);
+        assert_eq!(motor.duty_for_percent(101), 50);
+
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_drive_command_conversions() {
+        assert_eq!(
+            DriveCommand::try_from(0u8),
+            Ok(DriveCommand::forward_const(0))
+        );
+        assert_eq!(
+            DriveCommand::try_from(100u8),
+            Ok(DriveCommand::forward_const(100))
+        );
+        assert_eq!(DriveCommand::try_from(101u8), Err(InvalidSpeedError));
+        assert_eq!(DriveCommand::try_from(u8::MAX), Err(InvalidSpeedError));
+
+        assert_eq!(i8::from(DriveCommand::forward_const(75)), 75);
+        assert_eq!(i8::from(DriveCommand::backward_const(100)), -100);
+        assert_eq!(i8::from(DriveCommand::Brake), 0);
+        assert_eq!(i8::from(DriveCommand::Stop), 0);
+
+        // round-trip
+        for speed in -100..=100i8 {
+            assert_eq!(i8::from(DriveCommand::try_from(speed).unwrap()), speed);
+        }
+    }
+
+    #[test]
+    fn test_drive_command_speed() {
+        assert_eq!(DriveCommand::forward_const(75).speed(), Some(75));
+        assert_eq!(DriveCommand::backward_const(50).speed(), Some(50));
+        assert_eq!(DriveCommand::forward_const(0).speed(), Some(0));
+        assert_eq!(DriveCommand::Brake.speed(), None);
+        assert_eq!(DriveCommand::Stop.speed(), None);
+
+        assert_eq!(DriveCommand::forward_const(75).magnitude(), 75);
+        assert_eq!(DriveCommand::backward_const(50).magnitude(), 50);
+        assert_eq!(DriveCommand::forward_const(0).magnitude(), 0);
+        assert_eq!(DriveCommand::Brake.magnitude(), 0);
+        assert_eq!(DriveCommand::Stop.magnitude(), 0);
+    }
+
+    #[test]
+    fn test_tb6612fng_standby_enabled() {
+        let max_duty = 100;
+        let motor_a_in1 = PinMock::new(&[PinTransaction::set(Low)]);
+        let motor_a_in2 = PinMock::new(&[PinTransaction::set(Low)]);
+        let motor_a_pwm = PwmMock::new(&[
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+        ]);
+        let motor_b_in1 = PinMock::new(&[PinTransaction::set(Low)]);
+        let motor_b_in2 = PinMock::new(&[PinTransaction::set(Low)]);
+        let motor_b_pwm = PwmMock::new(&[
+            PwmTransaction::max_duty_cycle(max_duty),
+            PwmTransaction::set_duty_cycle(0),
+        ]);
+        let standby = PinMock::new(&[
+            PinTransaction::set(High),
+            PinTransaction::set(Low),
+            PinTransaction::set(High).with_error(MockError::Io(ErrorKind::NotConnected)),
+            PinTransaction::set(High),
+        ]);
+
+        let mut controller = Tb6612fng::new(
+            Motor::new(motor_a_in1, motor_a_in2, motor_a_pwm).unwrap(),
+            Motor::new(motor_b_in1, motor_b_in2, motor_b_pwm).unwrap(),
+            standby,
+        )
+        .unwrap();
+        assert!(!controller.standby_enabled());
+
+        controller.enable_standby().unwrap();
+        assert!(controller.standby_enabled());
+
+        // a failed pin interaction doesn't change the state
+        controller.disable_standby().unwrap_err();
+        assert!(controller.standby_enabled());
+
+        controller.disable_standby().unwrap();
+        assert!(!controller.standby_enabled());
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_drive_command_predicates() {
+        // (drive command, is_forward, is_backward, is_moving, is_braking, is_stopped, is_coasting)
+        let cases = [
+            (
+                DriveCommand::forward_const(50),
+                true,
+                false,
+                true,
+                false,
+                false,
+                false,
+            ),
+            (
+                DriveCommand::forward_const(0),
+                true,
+                false,
+                false,
+                false,
+                true,
+                false,
+            ),
+            (
+                DriveCommand::backward_const(50),
+                false,
+                true,
+                true,
+                false,
+                false,
+                false,
+            ),
+            (
+                DriveCommand::backward_const(0),
+                false,
+                true,
+                false,
+                false,
+                true,
+                false,
+            ),
+            (DriveCommand::Brake, false, false, false, true, false, false),
+            (DriveCommand::Stop, false, false, false, false, true, true),
+        ];
+
+        for (c, is_forward, is_backward, is_moving, is_braking, is_stopped, is_coasting) in cases {
+            assert_eq!(c.is_forward(), is_forward, "{:?}", c);
+            assert_eq!(c.is_backward(), is_backward, "{:?}", c);
+            assert_eq!(c.is_moving(), is_moving, "{:?}", c);
+            assert_eq!(c.is_braking(), is_braking, "{:?}", c);
+            assert_eq!(c.is_stopped(), is_stopped, "{:?}", c);
+            assert_eq!(c.is_coasting(), is_coasting, "{:?}", c);
+        }
+    }
+
+    #[test]
+    fn test_motor_on_drive() {
+        use core::sync::atomic::{AtomicI8, AtomicUsize, Ordering};
+
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        static LAST_SPEED: AtomicI8 = AtomicI8::new(0);
+
+        fn on_drive(drive_command: &DriveCommand) {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            LAST_SPEED.store(i8::from(*drive_command), Ordering::SeqCst);
+        }
+
+        let motor_in1_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_in2_expectations = [PinTransaction::set(High), PinTransaction::set(Low)];
+        let motor_pwm_expectations = [
+            PwmTransaction::set_duty_cycle(30),
+            PwmTransaction::set_duty_cycle(30),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(60),
+        ];
+        let mut motor = mock_motor(
+            &motor_in1_expectations,
+            &motor_in2_expectations,
+            &motor_pwm_expectations,
+            DriveCommand::Stop,
+        );
+        motor.set_on_drive(on_drive);
+
+        motor.drive(DriveCommand::backward_const(30)).unwrap();
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+        assert_eq!(LAST_SPEED.load(Ordering::SeqCst), -30);
+
+        // unchanged command => no notification
+        motor.drive(DriveCommand::backward_const(30)).unwrap();
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+
+        motor.clear_on_drive();
+        motor.drive(DriveCommand::forward_const(60)).unwrap();
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_drive_command_reverse() {
+        assert_eq!(
+            DriveCommand::forward_const(75).reverse(),
+            DriveCommand::backward_const(75)
+        );
+        assert_eq!(
+            DriveCommand::backward_const(75).reverse(),
+            DriveCommand::forward_const(75)
+        );
+        assert_eq!(DriveCommand::Brake.reverse(), DriveCommand::Brake);
+        assert_eq!(DriveCommand::Stop.reverse(), DriveCommand::Stop);
+
+        assert_eq!(
+            -DriveCommand::forward_const(75),
+            DriveCommand::backward_const(75)
+        );
+        assert_eq!(
+            -(-DriveCommand::forward_const(75)),
+            DriveCommand::forward_const(75)
+        );
+        assert_eq!(-DriveCommand::Brake, DriveCommand::Brake);
+
+        const REVERSED: DriveCommand = DriveCommand::forward_const(50).reverse();
+        assert_eq!(REVERSED, DriveCommand::backward_const(50));
+    }
+
+    #[test]
+    fn test_motor_max_speed() {
+        let motor_in1_expectations = [PinTransaction::set(High), PinTransaction::set(Low)];
+        let motor_in2_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_pwm_expectations = [
+            PwmTransaction::set_duty_cycle(80),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(50),
+        ];
+        let mut motor = mock_motor(
+            &motor_in1_expectations,
+            &motor_in2_expectations,
+            &motor_pwm_expectations,
+            DriveCommand::Stop,
+        );
+        assert_eq!(motor.max_speed(), 100);
+
+        motor.set_max_speed(80);
+        assert_eq!(motor.max_speed(), 80);
+
+        // clamped instead of rejected
+        motor.drive(DriveCommand::forward_const(100)).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(80)
+        );
+        assert_eq!(motor.current_speed(), 80);
+
+        // speeds below the maximum are unaffected
+        motor.drive(DriveCommand::backward_const(50)).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(50)
+        );
+
+        motor.set_max_speed(200);
+        assert_eq!(motor.max_speed(), 100);
+
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_drive_command_with_speed() {
+        assert_eq!(
+            DriveCommand::forward_const(50).with_speed(75),
+            Ok(DriveCommand::forward_const(75))
+        );
+        assert_eq!(
+            DriveCommand::backward_const(50).with_speed(0),
+            Ok(DriveCommand::backward_const(0))
+        );
+        assert_eq!(DriveCommand::Brake.with_speed(50), Ok(DriveCommand::Brake));
+        assert_eq!(DriveCommand::Stop.with_speed(50), Ok(DriveCommand::Stop));
+        assert_eq!(
+            DriveCommand::forward_const(50).with_speed(101),
+            Err(InvalidSpeedError)
+        );
+        assert_eq!(DriveCommand::Stop.with_speed(101), Err(InvalidSpeedError));
+
+        assert_eq!(
+            DriveCommand::forward_const(50).saturating_with_speed(75),
+            DriveCommand::forward_const(75)
+        );
+        assert_eq!(
+            DriveCommand::backward_const(50).saturating_with_speed(101),
+            DriveCommand::backward_const(100)
+        );
+        assert_eq!(
+            DriveCommand::Brake.saturating_with_speed(101),
+            DriveCommand::Brake
+        );
+        assert_eq!(
+            DriveCommand::Stop.saturating_with_speed(50),
+            DriveCommand::Stop
+        );
+    }
+
+    #[test]
+    fn test_tb6612fng_drive_arcade() {
+        let motor_a = mock_motor(
+            &[PinTransaction::set(High), PinTransaction::set(Low)],
+            &[PinTransaction::set(Low), PinTransaction::set(High)],
+            &[
+                PwmTransaction::set_duty_cycle(50),
+                PwmTransaction::max_duty_cycle(100),
+                PwmTransaction::set_duty_cycle(100),
+                PwmTransaction::set_duty_cycle(0),
+                PwmTransaction::max_duty_cycle(100),
+                PwmTransaction::set_duty_cycle(100),
+                PwmTransaction::max_duty_cycle(100),
+                PwmTransaction::set_duty_cycle(100),
+            ],
+            DriveCommand::Stop,
+        );
+        let motor_b = mock_motor(
+            &[PinTransaction::set(High), PinTransaction::set(Low)],
+            &[PinTransaction::set(Low), PinTransaction::set(High)],
+            &[
+                PwmTransaction::set_duty_cycle(50),
+                PwmTransaction::set_duty_cycle(40),
+                PwmTransaction::max_duty_cycle(100),
+                PwmTransaction::set_duty_cycle(100),
+                PwmTransaction::set_duty_cycle(0),
+                PwmTransaction::set_duty_cycle(60),
+            ],
+            DriveCommand::Stop,
+        );
+        let mut controller = mock_controller(motor_a, motor_b, &[], false);
+
+        // straight forward
+        controller.drive_arcade(50, 0).unwrap();
+        assert_eq!(controller.motor_a.current_speed(), 50);
+        assert_eq!(controller.motor_b.current_speed(), 50);
+
+        // turning right, the sum is clamped
+        controller.drive_arcade(70, 30).unwrap();
+        assert_eq!(controller.motor_a.current_speed(), 100);
+        assert_eq!(controller.motor_b.current_speed(), 40);
+
+        // turning left in place, the inputs are clamped
+        controller.drive_arcade(0, i8::MIN).unwrap();
+        assert_eq!(
+            *controller.motor_a.current_drive_command(),
+            DriveCommand::backward_const(100)
+        );
+        assert_eq!(controller.motor_b.current_speed(), 100);
+
+        // driving backward
+        controller.drive_arcade(-80, -20).unwrap();
+        assert_eq!(controller.motor_a.current_speed(), -100);
+        assert_eq!(controller.motor_b.current_speed(), -60);
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_drive_command_clamp_speed() {
+        assert_eq!(
+            DriveCommand::forward_const(10).clamp_speed(20, 80),
+            DriveCommand::forward_const(20)
+        );
+        assert_eq!(
+            DriveCommand::forward_const(50).clamp_speed(20, 80),
+            DriveCommand::forward_const(50)
+        );
+        assert_eq!(
+            DriveCommand::backward_const(90).clamp_speed(20, 80),
+            DriveCommand::backward_const(80)
+        );
+        assert_eq!(
+            DriveCommand::backward_const(0).clamp_speed(20, 80),
+            DriveCommand::backward_const(20)
+        );
+        assert_eq!(DriveCommand::Brake.clamp_speed(20, 80), DriveCommand::Brake);
+        assert_eq!(DriveCommand::Stop.clamp_speed(20, 80), DriveCommand::Stop);
+
+        const CLAMPED: DriveCommand = DriveCommand::forward_const(100).clamp_speed(20, 80);
+        assert_eq!(CLAMPED, DriveCommand::forward_const(80));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_drive_command_clamp_speed_invalid_range() {
+        let _ = DriveCommand::forward_const(50).clamp_speed(80, 20);
+    }
+
+    #[test]
+    fn test_motor_drive_permille() {
+        let motor_in1_expectations = [PinTransaction::set(High), PinTransaction::set(Low)];
+        let motor_in2_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_pwm_expectations = [
+            PwmTransaction::set_duty_cycle(157),
+            PwmTransaction::set_duty_cycle(1),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::max_duty_cycle(255),
+            PwmTransaction::set_duty_cycle(255),
+        ];
+        let mut motor = mock_motor(
+            &motor_in1_expectations,
+            &motor_in2_expectations,
+            &motor_pwm_expectations,
+            DriveCommand::Stop,
+        );
+        motor.max_duty = 255;
+
+        // 157.335 is rounded down and reported as 62%
+        motor.drive_permille(Direction::Forward, 617).unwrap();
+        assert_eq!(motor.current_duty_raw(), 157);
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(62)
+        );
+
+        // 0.51 is rounded up, 0.255 down
+        motor.drive_permille(Direction::Forward, 2).unwrap();
+        assert_eq!(motor.current_duty_raw(), 1);
+        motor.drive_permille(Direction::Forward, 1).unwrap();
+        assert_eq!(motor.current_duty_raw(), 0);
+
+        motor.drive_permille(Direction::Backward, 1000).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(100)
+        );
+
+        assert_eq!(
+            motor.drive_permille(Direction::Forward, 1001),
+            Err(MotorError::InvalidSpeed)
+        );
+
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_motor_drive_raw_deadband() {
+        let mut motor = mock_motor(
+            &[PinTransaction::set(High), PinTransaction::set(Low)],
+            &[PinTransaction::set(Low)],
+            &[
+                PwmTransaction::set_duty_cycle(200),
+                PwmTransaction::set_duty_cycle(0),
+                PwmTransaction::set_duty_cycle(0),
+            ],
+            DriveCommand::Stop,
+        );
+        motor.max_duty = 1000;
+        motor.set_deadband(20);
+
+        // exactly at the edge of the deadband the motor is driven
+        motor.drive_raw(Direction::Forward, 200).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(20)
+        );
+
+        // 19.9% and 0.5% are within the deadband and stop the motor
+        motor.drive_raw(Direction::Forward, 199).unwrap();
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+        assert_eq!(motor.current_speed(), 0);
+        motor.drive_permille(Direction::Backward, 5).unwrap();
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+        assert_eq!(motor.current_duty_raw(), 0);
+
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_motor_current_duty_raw() {
+        let motor_in1_expectations = [PinTransaction::set(High), PinTransaction::set(Low)];
+        let motor_in2_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_pwm_expectations = [
+            PwmTransaction::set_duty_cycle(1),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(42),
+        ];
+        let mut motor = mock_motor(
+            &motor_in1_expectations,
+            &motor_in2_expectations,
+            &motor_pwm_expectations,
+            DriveCommand::Stop,
+        );
+        motor.max_duty = 50;
+        assert_eq!(motor.current_duty_raw(), 0);
+
+        motor.drive(DriveCommand::forward_const(3)).unwrap();
+        assert_eq!(motor.current_duty_raw(), 1);
+
+        motor.drive_raw(Direction::Backward, 42).unwrap();
+        assert_eq!(motor.current_duty_raw(), 42);
+
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_drive_command_default() {
+        assert_eq!(DriveCommand::default(), DriveCommand::Stop);
+    }
+
+    #[test]
+    fn test_drive_command_constants() {
+        assert_eq!(DriveCommand::FORWARD_MAX, DriveCommand::forward_const(100));
+        assert_eq!(
+            DriveCommand::FORWARD_STOPPED,
+            DriveCommand::forward_const(0)
+        );
+        assert_eq!(
+            DriveCommand::BACKWARD_MAX,
+            DriveCommand::backward_const(100)
+        );
+        assert_eq!(
+            DriveCommand::BACKWARD_STOPPED,
+            DriveCommand::backward_const(0)
+        );
+
+        const SLOW: DriveCommand = DriveCommand::forward_const(10);
+        assert_eq!(SLOW, DriveCommand::forward_const(10));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_drive_command_forward_const_invalid_speed() {
+        let _ = DriveCommand::forward_const(101);
+    }
+
+    #[derive(Debug, Default)]
+    struct TwoChannelTimer {
+        duty: [u16; 2],
+    }
+
+    impl embedded_hal::pwm::ErrorType for TwoChannelTimer {
+        type Error = core::convert::Infallible;
+    }
+
+    impl MultiChannelPwm for TwoChannelTimer {
+        type Channel = usize;
+
+        fn max_duty_cycle(&self, _channel: usize) -> u16 {
+            200
+        }
+
+        fn set_duty_cycle(&mut self, channel: usize, duty: u16) -> Result<(), Self::Error> {
+            self.duty[channel] = duty;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_dual_channel_pwm() {
+        let motor_a_in1_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let motor_a_in2_expectations = [PinTransaction::set(Low)];
+        let motor_b_in1_expectations = [PinTransaction::set(Low)];
+        let motor_b_in2_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
+        let mut motor_a_in1 = PinMock::new(&motor_a_in1_expectations);
+        let mut motor_a_in2 = PinMock::new(&motor_a_in2_expectations);
+        let mut motor_b_in1 = PinMock::new(&motor_b_in1_expectations);
+        let mut motor_b_in2 = PinMock::new(&motor_b_in2_expectations);
+
+        let timer = DualChannelPwm::new(TwoChannelTimer::default());
+        {
+            let (pwm_a, pwm_b) = timer.split(0, 1);
+            let mut motor_a = Motor::new(motor_a_in1.clone(), motor_a_in2.clone(), pwm_a).unwrap();
+            let mut motor_b = Motor::new(motor_b_in1.clone(), motor_b_in2.clone(), pwm_b).unwrap();
+
+            motor_a.drive(DriveCommand::forward_const(50)).unwrap();
+            motor_b.drive(DriveCommand::backward_const(25)).unwrap();
+        }
+
+        assert_eq!(timer.release().duty, [100, 50]);
+
+        motor_a_in1.done();
+        motor_a_in2.done();
+        motor_b_in1.done();
+        motor_b_in2.done();
+    }
+
+    #[test]
+    fn test_drive_command_to_pin_states() {
+        assert_eq!(
+            DriveCommand::forward_const(50).to_pin_states(),
+            (true, false)
+        );
+        assert_eq!(
+            DriveCommand::backward_const(50).to_pin_states(),
+            (false, true)
+        );
+        assert_eq!(DriveCommand::Brake.to_pin_states(), (true, true));
+        assert_eq!(DriveCommand::Stop.to_pin_states(), (false, false));
+    }
+
+    /// A PWM pin which records whether the duty cycle has last been set fully off, fully on or to a specific value.
+    #[derive(Debug, Default)]
+    struct FullyOnOffPwm {
+        last: Option<(&'static str, u16)>,
+    }
+
+    impl embedded_hal::pwm::ErrorType for FullyOnOffPwm {
+        type Error = core::convert::Infallible;
+    }
+
+    impl SetDutyCycle for FullyOnOffPwm {
+        fn max_duty_cycle(&self) -> u16 {
+            1000
+        }
+
+        fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+            self.last = Some(("set_duty_cycle", duty));
+            Ok(())
+        }
+
+        fn set_duty_cycle_fully_off(&mut self) -> Result<(), Self::Error> {
+            self.last = Some(("set_duty_cycle_fully_off", 0));
+            Ok(())
+        }
+
+        fn set_duty_cycle_fully_on(&mut self) -> Result<(), Self::Error> {
+            self.last = Some(("set_duty_cycle_fully_on", 1000));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_motor_drive_fully_on_off() {
+        let log = OutputLog::default();
+        let pin = |name| LoggingPin {
+            name,
+            max_duty: 0,
+            log: log.clone(),
+        };
+        let mut motor = Motor::new_unchecked(pin("in1"), pin("in2"), FullyOnOffPwm::default());
+
+        let cases = [
+            (
+                DriveCommand::forward_const(100),
+                ("set_duty_cycle_fully_on", 1000),
+            ),
+            (DriveCommand::forward_const(50), ("set_duty_cycle", 500)),
+            (
+                DriveCommand::backward_const(0),
+                ("set_duty_cycle_fully_off", 0),
+            ),
+            (DriveCommand::Brake, ("set_duty_cycle_fully_off", 0)),
+        ];
+        for (drive_command, expected) in cases {
+            motor.drive(drive_command).unwrap();
+            assert_eq!(motor.pwm.last, Some(expected));
+        }
+
+        motor.drive_raw(Direction::Backward, 1000).unwrap();
+        assert_eq!(motor.pwm.last, Some(("set_duty_cycle_fully_on", 1000)));
+    }
+
+    #[derive(Debug)]
+    struct EnablePwmMock {
+        pwm: PwmMock,
+        enabled: bool,
+    }
+
+    impl embedded_hal::pwm::ErrorType for EnablePwmMock {
+        type Error = MockError;
+    }
+
+    impl SetDutyCycle for EnablePwmMock {
+        fn max_duty_cycle(&self) -> u16 {
+            self.pwm.max_duty_cycle()
+        }
+
+        fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+            self.pwm.set_duty_cycle(duty)
+        }
+    }
+
+    impl PwmEnable for EnablePwmMock {
+        fn enable(&mut self) -> Result<(), Self::Error> {
+            self.enabled = true;
+            Ok(())
+        }
+
+        fn disable(&mut self) -> Result<(), Self::Error> {
+            self.enabled = false;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_motor_disable_enable() {
+        let motor_in1_expectations = [
+            PinTransaction::set(Low),
+            PinTransaction::set(High),
+            PinTransaction::set(Low),
+        ];
+        let motor_in2_expectations = [PinTransaction::set(Low)];
+        let motor_pwm_expectations = [
+            PwmTransaction::max_duty_cycle(100),
+            PwmTransaction::set_duty_cycle(0),
+            PwmTransaction::set_duty_cycle(50),
+            PwmTransaction::set_duty_cycle(0),
+        ];
+        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
+        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
+        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+
+        let pwm = EnablePwmMock {
+            pwm: motor_pwm.clone(),
+            enabled: true,
+        };
+        let mut motor = Motor::new(motor_in1.clone(), motor_in2.clone(), pwm).unwrap();
+
+        motor.drive(DriveCommand::forward_const(50)).unwrap();
+        motor.disable().unwrap();
+        assert!(!motor.pwm.enabled);
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+
+        motor.enable().unwrap();
+        assert!(motor.pwm.enabled);
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_update_closed_loop() {
+        let mut motor = mock_motor(
+            &[PinTransaction::set(High), PinTransaction::set(Low)],
+            &[PinTransaction::set(Low)],
+            &[
+                PwmTransaction::set_duty_cycle(60),
+                PwmTransaction::set_duty_cycle(10),
+                PwmTransaction::set_duty_cycle(0),
+            ],
+            DriveCommand::Stop,
+        );
+        let gains = ClosedLoopGains { kp: 0.5, ki: 0.1 };
+        motor.set_closed_loop_gains(gains);
+        assert_eq!(motor.closed_loop_gains(), gains);
+
+        motor.update_closed_loop(100, 0).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(60)
+        );
+
+        // the integral term keeps the motor running once the target has been reached
+        motor.update_closed_loop(100, 100).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(10)
+        );
+
+        motor.reset_closed_loop();
+        motor.update_closed_loop(100, 100).unwrap();
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_motor_update_closed_loop_saturates() {
+        let mut motor = mock_motor(
+            &[PinTransaction::set(Low)],
+            &[PinTransaction::set(High)],
+            &[
+                PwmTransaction::max_duty_cycle(100),
+                PwmTransaction::set_duty_cycle(100),
+            ],
+            DriveCommand::Stop,
+        );
+        motor.set_closed_loop_gains(ClosedLoopGains { kp: 2.0, ki: 1.0 });
+
+        motor.update_closed_loop(-1000, 0).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(100)
+        );
+        assert_eq!(motor.closed_loop_integral, -100.0);
+
+        motor_done(&mut motor);
+    }
+
+    /// A [`SpeedFeedback`] which reports a fixed speed or fails if none is set.
+    struct FakeEncoder(Option<i32>);
+
+    impl SpeedFeedback for FakeEncoder {
+        type Error = ErrorKind;
+
+        fn measured_rpm(&mut self) -> Result<i32, Self::Error> {
+            self.0.ok_or(ErrorKind::NotConnected)
+        }
+    }
+
+    #[test]
+    fn test_motor_update_closed_loop_from() {
+        let mut motor = mock_motor(
+            &[PinTransaction::set(High)],
+            &[PinTransaction::set(Low)],
+            &[PwmTransaction::set_duty_cycle(25)],
+            DriveCommand::Stop,
+        );
+        motor.set_closed_loop_gains(ClosedLoopGains { kp: 0.5, ki: 0.0 });
+
+        motor
+            .update_closed_loop_from(100, &mut FakeEncoder(Some(50)))
+            .unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(25)
+        );
+
+        // the motor keeps running if the speed can't be measured
+        assert_eq!(
+            motor.update_closed_loop_from(100, &mut FakeEncoder(None)),
+            Err(ClosedLoopError::Feedback(ErrorKind::NotConnected))
+        );
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(25)
+        );
+
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_display() {
+        use std::format;
+
+        assert_eq!(
+            format!("{}", DriveCommand::forward_const(75)),
+            "Forward(75%)"
+        );
+        assert_eq!(
+            format!("{}", DriveCommand::backward_const(20)),
+            "Backward(20%)"
+        );
+        assert_eq!(format!("{}", DriveCommand::Brake), "Brake");
+        assert_eq!(format!("{}", DriveCommand::Stop), "Stop");
+
+        type Error = MotorError<ErrorKind, ErrorKind, ErrorKind>;
+        assert_eq!(
+            format!("{}", Error::InvalidSpeed),
+            "an invalid speed has been specified"
+        );
+        assert_eq!(
+            format!("{}", Error::In1Error(ErrorKind::Other)),
+            "failed to set the output of the IN1 pin"
+        );
+        assert_eq!(
+            format!("{}", Error::In2Error(ErrorKind::Other)),
+            "failed to set the output of the IN2 pin"
+        );
+        assert_eq!(
+            format!("{}", Error::PwmError(ErrorKind::Other)),
+            "failed to set the output of the PWM pin"
+        );
+
+        assert_eq!(
+            format!("{}", Tb6612fngError::Standby(ErrorKind::Other)),
+            "failed to set the output of the standby pin"
+        );
+    }
+
+    #[test]
+    fn test_hash() {
+        use std::collections::HashSet;
+
+        let commands: HashSet<DriveCommand> = [
+            DriveCommand::forward_const(50),
+            DriveCommand::forward_const(50),
+            DriveCommand::forward_const(60),
+            DriveCommand::backward_const(50),
+            DriveCommand::Brake,
+            DriveCommand::Stop,
+            DriveCommand::Stop,
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(commands.len(), 5);
+        assert!(commands.contains(&DriveCommand::backward_const(50)));
+
+        let errors: HashSet<MotorError<ErrorKind, ErrorKind, ErrorKind>> = [
+            MotorError::InvalidSpeed,
+            MotorError::InvalidSpeed,
+            MotorError::In1Error(ErrorKind::Other),
+            MotorError::In2Error(ErrorKind::Other),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(errors.len(), 3);
+
+        let errors: HashSet<Tb6612fngError<ErrorKind>> = [
+            Tb6612fngError::Standby(ErrorKind::Other),
+            Tb6612fngError::Standby(ErrorKind::Other),
+            Tb6612fngError::Standby(ErrorKind::NotFound),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn test_motor_decel_stop() {
+        let mut motor = mock_motor(
+            &[PinTransaction::set(Low)],
+            &[PinTransaction::set(High), PinTransaction::set(Low)],
+            &[
+                PwmTransaction::set_duty_cycle(30),
+                PwmTransaction::set_duty_cycle(10),
+                PwmTransaction::set_duty_cycle(0),
+            ],
+            DriveCommand::backward_const(50),
+        );
+
+        assert!(!motor.decel_stop(20).unwrap());
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(30)
+        );
+        assert!(!motor.decel_stop(20).unwrap());
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(10)
+        );
+        assert!(motor.decel_stop(20).unwrap());
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+
+        // already stopped: no pin interaction
+        assert!(motor.decel_stop(20).unwrap());
+
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_motor_decel_stop_from_brake() {
+        let mut motor = mock_motor(
+            &[PinTransaction::set(Low)],
+            &[PinTransaction::set(Low)],
+            &[PwmTransaction::set_duty_cycle(0)],
+            DriveCommand::Brake,
+        );
+
+        assert!(motor.decel_stop(20).unwrap());
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_tb6612fng_drive_motor_in_standby() {
+        let motor_a = mock_motor(
+            &[PinTransaction::set(High)],
+            &[PinTransaction::set(Low)],
+            &[PwmTransaction::set_duty_cycle(50)],
+            DriveCommand::Stop,
+        );
+        let motor_b = mock_motor(
+            &[PinTransaction::set(Low)],
+            &[PinTransaction::set(High)],
+            &[PwmTransaction::set_duty_cycle(30)],
+            DriveCommand::Stop,
+        );
+        let mut controller = mock_controller(motor_a, motor_b, &[PinTransaction::set(High)], true);
+
+        assert_eq!(
+            controller.drive_motor_a(DriveCommand::forward_const(50)),
+            Err(Tb6612fngDriveError::InStandby)
+        );
+        assert_eq!(
+            controller.drive_motor_b(DriveCommand::backward_const(30)),
+            Err(Tb6612fngDriveError::InStandby)
+        );
+        assert_eq!(
+            *controller.motor_a.current_drive_command(),
+            DriveCommand::Stop
+        );
+        assert_eq!(
+            *controller.motor_b.current_drive_command(),
+            DriveCommand::Stop
+        );
+
+        controller.disable_standby().unwrap();
+        controller
+            .drive_motor_a(DriveCommand::forward_const(50))
+            .unwrap();
+        controller
+            .drive_motor_b(DriveCommand::backward_const(30))
+            .unwrap();
+        assert_eq!(
+            *controller.motor_a.current_drive_command(),
+            DriveCommand::forward_const(50)
+        );
+        assert_eq!(
+            *controller.motor_b.current_drive_command(),
+            DriveCommand::backward_const(30)
+        );
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_drive_motor_tb6612fng_channels() {
+        fn drive_briefly<M: DriveMotor>(mut motor: M, speed: u8) -> Result<i8, M::Error> {
+            motor.drive(DriveCommand::forward_const(speed))?;
+            let current_speed = motor.current_speed();
+            motor.stop()?;
+            Ok(current_speed)
+        }
+
+        let motor_a = mock_motor(
+            &[PinTransaction::set(High), PinTransaction::set(Low)],
+            &[PinTransaction::set(Low)],
+            &[
+                PwmTransaction::set_duty_cycle(50),
+                PwmTransaction::set_duty_cycle(0),
+            ],
+            DriveCommand::Stop,
+        );
+        let motor_b = mock_motor(
+            &[PinTransaction::set(High), PinTransaction::set(Low)],
+            &[PinTransaction::set(Low)],
+            &[
+                PwmTransaction::set_duty_cycle(30),
+                PwmTransaction::set_duty_cycle(0),
+            ],
+            DriveCommand::Stop,
+        );
+        let mut controller = mock_controller(motor_a, motor_b, &[], false);
+
+        assert_eq!(drive_briefly(&mut controller.motor_a, 50), Ok(50));
+        assert_eq!(drive_briefly(controller.motor_b().unwrap(), 30), Ok(30));
+        assert_eq!(
+            *controller.motor_a.current_drive_command(),
+            DriveCommand::Stop
+        );
+        assert_eq!(
+            *controller.motor_b.current_drive_command(),
+            DriveCommand::Stop
+        );
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_tb6612fng_brake_all_stop_all() {
+        // (brake instead of stop, initial command of motor B, expected command, expected state of IN1 & IN2)
+        let cases = [
+            (
+                true,
+                DriveCommand::backward_const(50),
+                DriveCommand::Brake,
+                High,
+            ),
+            (false, DriveCommand::Brake, DriveCommand::Stop, Low),
+        ];
+
+        for (brake, motor_b_command, expected, state) in cases {
+            let motor_a = mock_motor(
+                &[PinTransaction::set(state)],
+                &[PinTransaction::set(state)],
+                &[PwmTransaction::set_duty_cycle(0)],
+                DriveCommand::forward_const(50),
+            );
+            let motor_b = mock_motor(
+                &[PinTransaction::set(state)],
+                &[PinTransaction::set(state)],
+                &[PwmTransaction::set_duty_cycle(0)],
+                motor_b_command,
+            );
+            let mut controller = mock_controller(motor_a, motor_b, &[], false);
+
+            if brake {
+                controller.brake_all().unwrap();
+            } else {
+                controller.stop_all().unwrap();
+            }
+
+            assert_eq!(*controller.motor_a.current_drive_command(), expected);
+            assert_eq!(*controller.motor_b.current_drive_command(), expected);
+
+            controller_done(controller);
+        }
+    }
+
+    #[test]
+    fn test_tb6612fng_snapshot() {
+        let motor_a = motor_with_drive_command(DriveCommand::forward_const(50));
+        let motor_b = motor_with_drive_command(DriveCommand::Brake);
+        let controller = mock_controller(motor_a, motor_b, &[], true);
+
+        assert_eq!(
+            controller.snapshot(),
+            ControllerSnapshot {
+                motor_a: DriveCommand::forward_const(50),
+                motor_b: DriveCommand::Brake,
+                standby_enabled: true,
+            }
+        );
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_tb6612fng_release_and_from_parts() {
+        let motor_a = motor_with_drive_command(DriveCommand::forward_const(50));
+        let motor_b = motor_with_drive_command(DriveCommand::Brake);
+        let controller = mock_controller(motor_a, motor_b, &[], true);
+
+        let (motor_a, motor_b, standby) = controller.release();
+        let controller = Tb6612fng::from_parts(motor_a, motor_b, standby, true);
+
+        assert!(controller.standby_enabled());
+        assert_eq!(
+            *controller.motor_a.current_drive_command(),
+            DriveCommand::forward_const(50)
+        );
+        assert_eq!(
+            *controller.motor_b.current_drive_command(),
+            DriveCommand::Brake
+        );
+
+        controller_done(controller);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize() {
+        use serde::de::value::{Error, StrDeserializer, U8Deserializer};
+        use serde::de::IntoDeserializer;
+        use serde::Deserialize;
+
+        let deserializer: StrDeserializer<Error> = "Brake".into_deserializer();
+        assert_eq!(
+            DriveCommand::deserialize(deserializer),
+            Ok(DriveCommand::Brake)
+        );
+
+        let deserializer: StrDeserializer<Error> = "Backward".into_deserializer();
+        assert_eq!(
+            Direction::deserialize(deserializer),
+            Ok(Direction::Backward)
+        );
+
+        let deserializer: StrDeserializer<Error> = "Sideways".into_deserializer();
+        assert!(DriveCommand::deserialize(deserializer).is_err());
+
+        let deserializer: U8Deserializer<Error> = 42u8.into_deserializer();
+        assert_eq!(
+            Percent::deserialize(deserializer),
+            Ok(Percent::new_const(42))
+        );
+
+        let deserializer: U8Deserializer<Error> = 101u8.into_deserializer();
+        assert!(Percent::deserialize(deserializer).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_drive_command_serde_round_trip() {
+        for drive_command in [
+            DriveCommand::forward_const(75),
+            DriveCommand::backward_const(25),
+            DriveCommand::Brake,
+            DriveCommand::Stop,
+        ] {
+            let json = serde_json::to_string(&drive_command).unwrap();
+            assert_eq!(
+                serde_json::from_str::<DriveCommand>(&json).unwrap(),
+                drive_command
+            );
+        }
+    }
+
+    #[test]
+    fn test_tb6612fng_motors_mut() {
+        let motor_a = mock_motor(
+            &[PinTransaction::set(High)],
+            &[PinTransaction::set(Low)],
+            &[PwmTransaction::set_duty_cycle(50)],
+            DriveCommand::Stop,
+        );
+        let motor_b = mock_motor(
+            &[PinTransaction::set(High)],
+            &[PinTransaction::set(High)],
+            &[PwmTransaction::set_duty_cycle(0)],
+            DriveCommand::Stop,
+        );
+        let mut controller = mock_controller(motor_a, motor_b, &[], false);
+
+        let (motor_a, motor_b) = controller.motors_mut();
+        motor_a.drive(DriveCommand::forward_const(50)).unwrap();
+        motor_b.brake().unwrap();
+
+        assert_eq!(
+            controller.snapshot(),
+            ControllerSnapshot {
+                motor_a: DriveCommand::forward_const(50),
+                motor_b: DriveCommand::Brake,
+                standby_enabled: false,
+            }
+        );
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_motor_new_with_frequency() {
+        let mut motor_in1 = PinMock::new(&[PinTransaction::set(Low)]);
+        let mut motor_in2 = PinMock::new(&[PinTransaction::set(Low)]);
+        let mut motor_pwm = PwmMock::new(&[
+            PwmTransaction::max_duty_cycle(100),
+            PwmTransaction::set_duty_cycle(0),
+        ]);
+
+        let motor = Motor::new_with_frequency(
+            motor_in1.clone(),
+            motor_in2.clone(),
+            motor_pwm.clone(),
+            20_000,
+        )
+        .unwrap();
+
+        assert_eq!(motor.pwm_frequency(), Some(20_000));
+
+        let mut motor = motor_with_drive_command(DriveCommand::Stop);
+        assert_eq!(motor.pwm_frequency(), None);
+        motor_done(&mut motor);
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_tb6612fng_standby_pin_access() {
+        let motor_a = motor_with_drive_command(DriveCommand::Stop);
+        let motor_b = motor_with_drive_command(DriveCommand::Stop);
+        let mut controller = mock_controller(motor_a, motor_b, &[PinTransaction::set(Low)], false);
+
+        controller.standby_pin_mut().set_low().unwrap();
+
+        // the cached state is not updated when bypassing the controller
+        assert!(!controller.standby_enabled());
+        let _ = controller.standby_pin_ref();
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_motor_array_drive_all() {
+        let motors = [
+            mock_motor(
+                &[PinTransaction::set(High)],
+                &[PinTransaction::set(Low)],
+                &[PwmTransaction::set_duty_cycle(10)],
+                DriveCommand::Stop,
+            ),
+            mock_motor(
+                &[PinTransaction::set(Low)],
+                &[PinTransaction::set(High)],
+                &[PwmTransaction::set_duty_cycle(20)],
+                DriveCommand::Stop,
+            ),
+            mock_motor(
+                &[PinTransaction::set(High)],
+                &[PinTransaction::set(High)],
+                &[PwmTransaction::set_duty_cycle(0)],
+                DriveCommand::Stop,
+            ),
+        ];
+        let mut motors = MotorArray::new(motors);
+
+        motors
+            .drive_all(&[
+                DriveCommand::forward_const(10),
+                DriveCommand::backward_const(20),
+                DriveCommand::Brake,
+            ])
+            .unwrap();
+
+        assert_eq!(
+            *motors.get(1).unwrap().current_drive_command(),
+            DriveCommand::backward_const(20)
+        );
+        assert!(motors.get(3).is_none());
+
+        for motor in motors.motors_mut() {
+            motor_done(motor);
+        }
+    }
+
+    #[test]
+    fn test_motor_array_drive_all_error() {
+        let motors = [
+            mock_motor(
+                &[PinTransaction::set(High)],
+                &[PinTransaction::set(Low)],
+                &[PwmTransaction::set_duty_cycle(10)],
+                DriveCommand::Stop,
+            ),
+            mock_motor(
+                &[PinTransaction::set(Low).with_error(MockError::Io(ErrorKind::NotConnected))],
+                &[],
+                &[],
+                DriveCommand::Stop,
+            ),
+            motor_with_drive_command(DriveCommand::Stop),
+        ];
+        let mut motors = MotorArray::new(motors);
+
+        assert_eq!(
+            motors.drive_all(&[
+                DriveCommand::forward_const(10),
+                DriveCommand::backward_const(20),
+                DriveCommand::Brake,
+            ]),
+            Err(MotorArrayDriveError {
+                index: 1,
+                error: MotorError::In1Error(MockError::Io(ErrorKind::NotConnected)),
+            })
+        );
+        assert_eq!(
+            *motors.get(2).unwrap().current_drive_command(),
+            DriveCommand::Stop
+        );
+
+        for mut motor in motors.release() {
+            motor_done(&mut motor);
+        }
+    }
+
+    #[test]
+    fn test_tb6612fng_toggle_standby() {
+        let motor_a = motor_with_drive_command(DriveCommand::Stop);
+        let motor_b = motor_with_drive_command(DriveCommand::Stop);
+        let mut controller = mock_controller(
+            motor_a,
+            motor_b,
+            &[
+                PinTransaction::get_state(High),
+                PinTransaction::set(Low),
+                PinTransaction::get_state(Low),
+                PinTransaction::set(High),
+            ],
+            false,
+        );
+
+        controller.toggle_standby().unwrap();
+        assert!(controller.standby_enabled());
+
+        controller.toggle_standby().unwrap();
+        assert!(!controller.standby_enabled());
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_tb6612fng_pulse_standby() {
+        let motor_a = motor_with_drive_command(DriveCommand::Stop);
+        let motor_b = motor_with_drive_command(DriveCommand::Stop);
+        let mut controller = mock_controller(
+            motor_a,
+            motor_b,
+            &[PinTransaction::set(Low), PinTransaction::set(High)],
+            false,
+        );
+
+        controller.pulse_standby().unwrap();
+        assert!(!controller.standby_enabled());
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_tb6612fng_current_and_electrical_standby_state() {
+        let motor_a = motor_with_drive_command(DriveCommand::Stop);
+        let motor_b = motor_with_drive_command(DriveCommand::Stop);
+        let mut controller = mock_controller(
+            motor_a,
+            motor_b,
+            &[
+                PinTransaction::get_state(High),
+                PinTransaction::get_state(High),
+                PinTransaction::get_state(Low),
+                PinTransaction::get_state(Low),
+            ],
+            false,
+        );
+
+        assert!(!controller.current_standby().unwrap());
+        assert!(controller.electrical_standby_state().unwrap());
+        assert!(controller.current_standby().unwrap());
+        assert!(!controller.electrical_standby_state().unwrap());
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_tb6612fng_new_in_standby() {
+        let motor_a = motor_with_drive_command(DriveCommand::Stop);
+        let motor_b = motor_with_drive_command(DriveCommand::Stop);
+        let standby = PinMock::new(&[PinTransaction::set(Low)]);
+
+        let controller = Tb6612fng::new_in_standby(motor_a, motor_b, standby).unwrap();
+        assert!(controller.standby_enabled());
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_tb6612fng_new_in_standby_error() {
+        let motor_a = motor_with_drive_command(DriveCommand::Stop);
+        let motor_b = motor_with_drive_command(DriveCommand::Stop);
+        let mut standby = PinMock::new(&[
+            PinTransaction::set(Low).with_error(MockError::Io(ErrorKind::NotConnected))
+        ]);
+
+        // the motors are dropped together with the controller, so keep handles to their mocks
+        let mut pin_mocks = [
+            motor_a.in1.clone(),
+            motor_a.in2.clone(),
+            motor_b.in1.clone(),
+            motor_b.in2.clone(),
+        ];
+        let mut pwm_mocks = [motor_a.pwm.clone(), motor_b.pwm.clone()];
+
+        let error = Tb6612fng::new_in_standby(motor_a, motor_b, standby.clone()).unwrap_err();
+        assert_eq!(
+            error,
+            Tb6612fngError::Standby(MockError::Io(ErrorKind::NotConnected))
+        );
+
+        pin_mocks.iter_mut().for_each(PinMock::done);
+        pwm_mocks.iter_mut().for_each(PwmMock::done);
+        standby.done();
+    }
+
+    #[test]
+    fn test_percent() {
+        assert_eq!(Percent::new(0), Some(Percent::MIN));
+        assert_eq!(Percent::new(100), Some(Percent::MAX));
+        assert_eq!(Percent::new(101), None);
+        assert_eq!(Percent::saturating_new(255), Percent::MAX);
+        assert_eq!(Percent::try_from(42).map(u8::from), Ok(42));
+        assert_eq!(Percent::try_from(101), Err(InvalidSpeedError));
+
+        assert_eq!(
+            DriveCommand::forward(Percent::new_const(30)),
+            DriveCommand::forward_const(30)
+        );
+        assert_eq!(
+            DriveCommand::backward(Percent::MAX),
+            DriveCommand::backward_const(100)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_percent_new_const_invalid() {
+        let _ = Percent::new_const(101);
+    }
+
+    #[test]
+    fn test_drive_command_unchecked() {
+        const FAST: DriveCommand = DriveCommand::forward_unchecked(40 * 2);
+        assert_eq!(FAST, DriveCommand::forward_const(80));
+        assert_eq!(
+            DriveCommand::backward_unchecked(100),
+            DriveCommand::BACKWARD_MAX
+        );
+        assert_eq!(
+            DriveCommand::forward_unchecked(0),
+            DriveCommand::FORWARD_STOPPED
+        );
+        let speed: Speed = Percent::new_const(20);
+        assert_eq!(
+            DriveCommand::forward(speed),
+            DriveCommand::forward_const(20)
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn test_drive_command_unchecked_invalid_speed() {
+        let _ = DriveCommand::backward_unchecked(101);
+    }
+
+    #[test]
+    fn test_tb6612fng_standby_scope() {
+        let motor_a = mock_motor(
+            &[PinTransaction::set(High)],
+            &[PinTransaction::set(Low)],
+            &[PwmTransaction::set_duty_cycle(50)],
+            DriveCommand::Stop,
+        );
+        let motor_b = motor_with_drive_command(DriveCommand::Stop);
+        let mut controller = mock_controller(
+            motor_a,
+            motor_b,
+            &[PinTransaction::set(Low), PinTransaction::set(High)],
+            false,
+        );
+
+        {
+            let mut guard = controller.standby_scope().unwrap();
+            assert!(guard.standby_enabled());
+            guard
+                .motor_a
+                .drive(DriveCommand::forward_const(50))
+                .unwrap();
+        }
+        assert!(!controller.standby_enabled());
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_tb6612fng_standby_scope_already_in_standby() {
+        let motor_a = motor_with_drive_command(DriveCommand::Stop);
+        let motor_b = motor_with_drive_command(DriveCommand::Stop);
+        let mut controller = mock_controller(
+            motor_a,
+            motor_b,
+            &[PinTransaction::set(Low), PinTransaction::set(Low)],
+            true,
+        );
+
+        let guard = controller.standby_scope().unwrap();
+        guard.release().unwrap();
+        assert!(controller.standby_enabled());
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_tb6612fng_standby_scope_drop_error() {
+        let motor_a = motor_with_drive_command(DriveCommand::Stop);
+        let motor_b = motor_with_drive_command(DriveCommand::Stop);
+        let mut controller = mock_controller(
+            motor_a,
+            motor_b,
+            &[
+                PinTransaction::set(Low),
+                PinTransaction::set(High).with_error(MockError::Io(ErrorKind::NotConnected)),
+            ],
+            false,
+        );
+
+        drop(controller.standby_scope().unwrap());
+        // restoring failed, thus standby is still enabled
+        assert!(controller.standby_enabled());
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_motor_set_speed() {
+        let mut motor = mock_motor(
+            &[PinTransaction::set(Low), PinTransaction::set(High)],
+            &[PinTransaction::set(High), PinTransaction::set(Low)],
+            &[
+                PwmTransaction::set_duty_cycle(80),
+                PwmTransaction::set_duty_cycle(0),
+                PwmTransaction::set_duty_cycle(30),
+            ],
+            DriveCommand::backward_const(0),
+        );
+
+        motor.set_speed(Percent::new_const(80)).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(80)
+        );
+
+        motor.current_drive_command = DriveCommand::Brake;
+        motor.set_speed(Percent::new_const(30)).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(30)
+        );
+
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_tb6612fng_differential_drive() {
+        let motor_a = mock_motor(
+            &[PinTransaction::set(High), PinTransaction::set(Low)],
+            &[PinTransaction::set(Low), PinTransaction::set(High)],
+            &[
+                PwmTransaction::max_duty_cycle(100),
+                PwmTransaction::set_duty_cycle(100),
+                PwmTransaction::set_duty_cycle(0),
+                PwmTransaction::max_duty_cycle(100),
+                PwmTransaction::set_duty_cycle(100),
+            ],
+            DriveCommand::Stop,
+        );
+        let motor_b = mock_motor(
+            &[PinTransaction::set(High)],
+            &[PinTransaction::set(Low)],
+            &[
+                PwmTransaction::set_duty_cycle(30),
+                PwmTransaction::max_duty_cycle(100),
+                PwmTransaction::set_duty_cycle(100),
+            ],
+            DriveCommand::Stop,
+        );
+        let mut controller = mock_controller(motor_a, motor_b, &[], false);
+
+        // a negative angular speed turns right (clockwise), the sum is clamped
+        controller.differential_drive(80, -50).unwrap();
+        assert_eq!(
+            controller.snapshot(),
+            ControllerSnapshot {
+                motor_a: DriveCommand::forward_const(100),
+                motor_b: DriveCommand::forward_const(30),
+                standby_enabled: false,
+            }
+        );
+
+        // a positive angular speed turns left (counterclockwise), without a linear speed on the spot
+        controller.differential_drive(0, 100).unwrap();
+        assert_eq!(
+            controller.snapshot(),
+            ControllerSnapshot {
+                motor_a: DriveCommand::backward_const(100),
+                motor_b: DriveCommand::forward_const(100),
+                standby_enabled: false,
+            }
+        );
+
+        // invalid inputs are rejected without driving any motor
+        assert_eq!(
+            controller.differential_drive(-101, 0),
+            Err(Tb6612fngDriveError::InvalidSpeed)
+        );
+        assert_eq!(
+            controller.differential_drive(0, 127),
+            Err(Tb6612fngDriveError::InvalidSpeed)
+        );
+        assert_eq!(controller.motor_a.current_speed(), -100);
+        assert_eq!(controller.motor_b.current_speed(), 100);
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_tb6612fng_try_drive_both() {
+        let motor_a = mock_motor(
+            &[PinTransaction::set(High).with_error(MockError::Io(ErrorKind::NotConnected))],
+            &[],
+            &[],
+            DriveCommand::Stop,
+        );
+        let motor_b = mock_motor(
+            &[PinTransaction::set(Low)],
+            &[PinTransaction::set(High)],
+            &[PwmTransaction::set_duty_cycle(40)],
+            DriveCommand::Stop,
+        );
+        let mut controller = mock_controller(motor_a, motor_b, &[], false);
+
+        let results = controller.try_drive_both(
+            DriveCommand::forward_const(40),
+            DriveCommand::backward_const(40),
+        );
+
+        assert!(!results.is_ok());
+        assert_eq!(
+            results.motor_a,
+            Err(MotorError::In1Error(MockError::Io(ErrorKind::NotConnected)))
+        );
+        assert_eq!(results.motor_b, Ok(()));
+        assert_eq!(
+            results.into_result(),
+            Err(Tb6612fngDriveError::MotorA(MotorError::In1Error(
+                MockError::Io(ErrorKind::NotConnected)
+            )))
+        );
+        assert_eq!(
+            *controller.motor_b.current_drive_command(),
+            DriveCommand::backward_const(40)
+        );
+
+        controller_done(controller);
+    }
+
+    /// The scenarios of driving forward at 50% for 500ms, shared by [`Motor::drive_for`] and
+    /// [`AsyncMotor::drive_for`]: (IN2 expectations, PWM expectations, expected delays, expected result)
+    #[allow(clippy::type_complexity)]
+    fn drive_for_cases() -> [(
+        std::vec::Vec<PinTransaction>,
+        std::vec::Vec<PwmTransaction>,
+        std::vec::Vec<DelayTransaction>,
+        Result<(), MotorError<MockError, MockError, MockError>>,
+    ); 2] {
+        [
+            (
+                std::vec![PinTransaction::set(Low)],
+                std::vec![
+                    PwmTransaction::set_duty_cycle(50),
+                    PwmTransaction::set_duty_cycle(0),
+                ],
+                std::vec![DelayTransaction::delay_ms(500)],
+                Ok(()),
+            ),
+            // a failing pin stops the motor without waiting
+            (
+                std::vec![
+                    PinTransaction::set(Low).with_error(MockError::Io(ErrorKind::NotConnected)),
+                    PinTransaction::set(Low),
+                ],
+                std::vec![PwmTransaction::set_duty_cycle(0)],
+                std::vec![],
+                Err(MotorError::In2Error(MockError::Io(ErrorKind::NotConnected))),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_motor_drive_for() {
+        for (in2_expectations, pwm_expectations, delays, expected) in drive_for_cases() {
+            let mut motor = mock_motor(
+                &[PinTransaction::set(High), PinTransaction::set(Low)],
+                &in2_expectations,
+                &pwm_expectations,
+                DriveCommand::Stop,
+            );
+            let mut delay = CheckedDelay::new(&delays);
+
+            assert_eq!(
+                motor.drive_for(DriveCommand::forward_const(50), 500, &mut delay),
+                expected
+            );
+            assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+
+            motor_done(&mut motor);
+            delay.done();
+        }
+    }
+
+    /// Poll the future until it is ready, the mocks used in the tests never return `Poll::Pending`.
+    #[cfg(feature = "async-hal")]
+    fn block_on<F: core::future::Future>(future: F) -> F::Output {
+        struct NoopWaker;
+
+        impl std::task::Wake for NoopWaker {
+            fn wake(self: std::sync::Arc<Self>) {}
+        }
+
+        let waker = std::task::Waker::from(std::sync::Arc::new(NoopWaker));
+        let mut context = core::task::Context::from_waker(&waker);
+        let mut future = core::pin::pin!(future);
+        loop {
+            if let core::task::Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "async-hal")]
+    fn test_async_motor_drive() {
+        let mut motor = AsyncMotor::from(mock_motor(
+            &[PinTransaction::set(High)],
+            &[PinTransaction::set(Low)],
+            &[PwmTransaction::set_duty_cycle(50)],
+            DriveCommand::Stop,
+        ));
+
+        block_on(motor.drive(DriveCommand::forward_const(50))).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(50)
+        );
+        assert_eq!(motor.current_speed(), 50);
+
+        motor_done(motor.inner_mut());
+    }
+
+    #[test]
+    #[cfg(feature = "async-hal")]
+    fn test_async_motor_drive_for() {
+        for (in2_expectations, pwm_expectations, delays, expected) in drive_for_cases() {
+            let mut motor = AsyncMotor::from(mock_motor(
+                &[PinTransaction::set(High), PinTransaction::set(Low)],
+                &in2_expectations,
+                &pwm_expectations,
+                DriveCommand::Stop,
+            ));
+            let mut delay = CheckedDelay::new(&delays);
+
+            assert_eq!(
+                block_on(motor.drive_for(DriveCommand::forward_const(50), 500, &mut delay)),
+                expected
+            );
+            assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+
+            motor_done(motor.inner_mut());
+            delay.done();
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "async-hal")]
+    fn test_async_motor_drive_with_reversal_guard() {
+        let mut motor = AsyncMotor::from(mock_motor(
+            &[PinTransaction::set(High), PinTransaction::set(Low)],
+            &[PinTransaction::set(High)],
+            &[
+                PwmTransaction::set_duty_cycle(0),
+                PwmTransaction::set_duty_cycle(30),
+                PwmTransaction::set_duty_cycle(20),
+            ],
+            DriveCommand::forward_const(50),
+        ));
+        let mut delay = CheckedDelay::new(&[DelayTransaction::async_delay_ms(100)]);
+
+        // reversing the direction brakes first
+        block_on(motor.drive_with_reversal_guard(
+            DriveCommand::backward_const(30),
+            100,
+            &mut delay,
+        ))
+        .unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(30)
+        );
+
+        // keeping the direction doesn't brake
+        block_on(motor.drive_with_reversal_guard(
+            DriveCommand::backward_const(20),
+            100,
+            &mut delay,
+        ))
+        .unwrap();
+
+        motor_done(motor.inner_mut());
+        delay.done();
+    }
+
+    #[cfg(feature = "async-hal")]
+    fn async_controller_done(
+        controller: AsyncTb6612fng<PinMock, PinMock, PwmMock, PinMock, PinMock, PwmMock, PinMock>,
+    ) {
+        let (mut motor_a, mut motor_b, mut standby) = controller.release();
+        motor_done(motor_a.inner_mut());
+        motor_done(motor_b.inner_mut());
+        standby.done();
+    }
+
+    #[test]
+    #[cfg(feature = "async-hal")]
+    fn test_async_tb6612fng_new() {
+        let controller = AsyncTb6612fng::new(
+            AsyncMotor::from(motor_with_drive_command(DriveCommand::Stop)),
+            AsyncMotor::from(motor_with_drive_command(DriveCommand::Stop)),
+            PinMock::new(&[PinTransaction::set(High)]),
+        )
+        .unwrap();
+        assert!(!controller.standby_enabled());
+
+        async_controller_done(controller);
+    }
+
+    #[test]
+    #[cfg(feature = "async-hal")]
+    fn test_async_tb6612fng_standby() {
+        let mut controller = AsyncTb6612fng::from(mock_controller(
+            motor_with_drive_command(DriveCommand::forward_const(50)),
+            motor_with_drive_command(DriveCommand::Stop),
+            &[PinTransaction::set(Low), PinTransaction::set(High)],
+            false,
+        ));
+
+        block_on(controller.enable_standby()).unwrap();
+        assert!(controller.standby_enabled());
+        // the motors keep their commands while in standby
+        assert_eq!(
+            *controller.motor_a.current_drive_command(),
+            DriveCommand::forward_const(50)
+        );
+
+        block_on(controller.disable_standby()).unwrap();
+        assert!(!controller.standby_enabled());
+
+        async_controller_done(controller);
+    }
+
+    #[test]
+    #[cfg(feature = "async-hal")]
+    fn test_async_tb6612fng_drive() {
+        let mut controller = AsyncTb6612fng::from(mock_controller(
+            mock_motor(
+                &[PinTransaction::set(High)],
+                &[PinTransaction::set(Low)],
+                &[PwmTransaction::set_duty_cycle(50)],
+                DriveCommand::Stop,
+            ),
+            mock_motor(
+                &[PinTransaction::set(Low)],
+                &[PinTransaction::set(High).with_error(MockError::Io(ErrorKind::NotConnected))],
+                &[],
+                DriveCommand::Stop,
+            ),
+            &[],
+            false,
+        ));
+
+        assert_eq!(
+            block_on(controller.drive(
+                DriveCommand::forward_const(50),
+                DriveCommand::backward_const(30)
+            )),
+            Err(Tb6612fngDriveError::MotorB(MotorError::In2Error(
+                MockError::Io(ErrorKind::NotConnected)
+            )))
+        );
+        assert_eq!(
+            *controller.motor_a.current_drive_command(),
+            DriveCommand::forward_const(50)
+        );
+
+        async_controller_done(controller);
+    }
+
+    #[test]
+    fn test_direction() {
+        assert_eq!(
+            DriveCommand::forward_const(0).direction(),
+            Some(Direction::Forward)
+        );
+        assert_eq!(
+            DriveCommand::backward_const(50).direction(),
+            Some(Direction::Backward)
+        );
+        assert_eq!(DriveCommand::Brake.direction(), None);
+        assert_eq!(DriveCommand::Stop.direction(), None);
+
+        assert_eq!(
+            DriveCommand::from((Direction::Backward, Percent::new_const(20))),
+            DriveCommand::backward_const(20)
+        );
+        assert_eq!(
+            DriveCommand::from((Direction::Forward, Percent::MAX)),
+            DriveCommand::forward_const(100)
+        );
+
+        let mut motor = motor_with_drive_command(DriveCommand::backward_const(10));
+        assert_eq!(motor.direction(), Some(Direction::Backward));
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_motor_max_duty() {
+        let mut motor_in1 = PinMock::new(&[]);
+        let mut motor_in2 = PinMock::new(&[]);
+        let mut motor_pwm = PwmMock::new(&[PwmTransaction::max_duty_cycle(4096)]);
+
+        let motor = Motor::new_unchecked(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone());
+        assert_eq!(motor.max_duty(), 4096);
+
+        motor_in1.done();
+        motor_in2.done();
+        motor_pwm.done();
+    }
+
+    #[test]
+    fn test_motor_capture_and_restore_state() {
+        // (drive command, in1, in2, duty)
+        let cases = [
+            (DriveCommand::forward_const(50), High, Low, 50),
+            (DriveCommand::backward_const(30), Low, High, 30),
+            (DriveCommand::Brake, High, High, 0),
+            (DriveCommand::Stop, Low, Low, 0),
+        ];
+
+        for (drive_command, in1, in2, duty) in cases {
+            let mut captured_motor = motor_with_drive_command(drive_command);
+            let state = captured_motor.capture_state();
+            assert_eq!(state.drive_command, drive_command);
+            motor_done(&mut captured_motor);
+
+            let mut motor = mock_motor(
+                &[PinTransaction::set(in1)],
+                &[PinTransaction::set(in2)],
+                &[PwmTransaction::set_duty_cycle(duty)],
+                DriveCommand::Stop,
+            );
+            motor.restore_state(state).unwrap();
+            assert_eq!(motor.capture_state(), state);
+            motor_done(&mut motor);
+        }
+    }
+
+    #[test]
+    fn test_motor_restore_state_error() {
+        let mut motor = mock_motor(
+            &[PinTransaction::set(High)],
+            &[PinTransaction::set(Low)],
+            &[PwmTransaction::set_duty_cycle(50)
+                .with_error(MockError::Io(ErrorKind::NotConnected))],
+            DriveCommand::Stop,
+        );
+        let state = MotorState {
+            drive_command: DriveCommand::forward_const(50),
+        };
+
+        assert_eq!(
+            motor.restore_state(state),
+            Err(MotorError::PwmError(MockError::Io(ErrorKind::NotConnected)))
+        );
+        assert_eq!(motor.capture_state().drive_command, DriveCommand::Stop);
+        assert_eq!(
+            motor.last_failed_drive_command(),
+            Some(DriveCommand::forward_const(50))
+        );
+
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_sequence_run() {
+        // (steps, IN2 expectations, PWM expectations, expected delays, expected result)
+        let cases = [
+            (
+                std::vec![
+                    (DriveCommand::forward_const(50), 500),
+                    (DriveCommand::Brake, 100),
+                ],
+                std::vec![
+                    PinTransaction::set(Low),
+                    PinTransaction::set(High),
+                    PinTransaction::set(Low),
+                ],
+                std::vec![
+                    PwmTransaction::set_duty_cycle(50),
+                    PwmTransaction::set_duty_cycle(0),
+                    PwmTransaction::set_duty_cycle(0),
+                ],
+                std::vec![
+                    DelayTransaction::delay_ms(500),
+                    DelayTransaction::delay_ms(100),
+                ],
+                Ok(()),
+            ),
+            // a failing step aborts the sequence and stops the motor
+            (
+                std::vec![
+                    (DriveCommand::forward_const(50), 500),
+                    (DriveCommand::backward_const(100), 100),
+                    (DriveCommand::Brake, 100),
+                ],
+                std::vec![
+                    PinTransaction::set(Low),
+                    PinTransaction::set(High).with_error(MockError::Io(ErrorKind::NotConnected)),
+                    PinTransaction::set(Low),
+                ],
+                std::vec![
+                    PwmTransaction::set_duty_cycle(50),
+                    PwmTransaction::set_duty_cycle(0),
+                    PwmTransaction::set_duty_cycle(0),
+                ],
+                std::vec![DelayTransaction::delay_ms(500)],
+                Err(MotorError::In2Error(MockError::Io(ErrorKind::NotConnected))),
+            ),
+        ];
+
+        for (steps, in2_expectations, pwm_expectations, delays, expected) in cases {
+            let mut motor = mock_motor(
+                &[PinTransaction::set(High), PinTransaction::set(Low)],
+                &in2_expectations,
+                &pwm_expectations,
+                DriveCommand::Stop,
+            );
+            let mut delay = CheckedDelay::new(&delays);
+
+            assert_eq!(Sequence::new(&steps).run(&mut motor, &mut delay), expected);
+            assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+
+            motor_done(&mut motor);
+            delay.done();
+        }
+    }
+
+    #[test]
+    fn test_drive_command_signed_speed() {
+        assert_eq!(DriveCommand::forward_const(42).signed_speed(), 42);
+        assert_eq!(DriveCommand::backward_const(42).signed_speed(), -42);
+        assert_eq!(DriveCommand::Brake.signed_speed(), 0);
+        assert_eq!(DriveCommand::Stop.signed_speed(), 0);
+
+        assert!(
+            DriveCommand::backward_const(10).signed_speed() < DriveCommand::Stop.signed_speed()
+        );
+    }
+
+    #[test]
+    fn test_bounded_speed() {
+        type FanSpeed = BoundedSpeed<20, 90>;
+
+        assert_eq!(FanSpeed::new(19), None);
+        assert_eq!(FanSpeed::new(20).map(u8::from), Some(20));
+        assert_eq!(FanSpeed::new(90).map(u8::from), Some(90));
+        assert_eq!(FanSpeed::new(91), None);
+        assert_eq!(FanSpeed::saturating_new(0).get(), 20);
+        assert_eq!(FanSpeed::saturating_new(255).get(), 90);
+
+        let mut motor = mock_motor(
+            &[PinTransaction::set(Low)],
+            &[PinTransaction::set(High)],
+            &[PwmTransaction::set_duty_cycle(50)],
+            DriveCommand::Stop,
+        );
+        motor
+            .drive_bounded(Direction::Backward, FanSpeed::new(50).unwrap())
+            .unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(50)
+        );
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_tb6612fng_emergency_stop() {
+        let motor_a = mock_motor(
+            &[PinTransaction::set(High)],
+            &[PinTransaction::set(High)],
+            &[PwmTransaction::set_duty_cycle(0)],
+            DriveCommand::forward_const(100),
+        );
+        let motor_b = mock_motor(
+            &[PinTransaction::set(High)],
+            &[PinTransaction::set(High)],
+            &[PwmTransaction::set_duty_cycle(0)],
+            DriveCommand::backward_const(100),
+        );
+        let mut controller = mock_controller(motor_a, motor_b, &[PinTransaction::set(Low)], false);
+
+        controller.emergency_stop().unwrap();
+
+        assert_eq!(
+            controller.snapshot(),
+            ControllerSnapshot {
+                motor_a: DriveCommand::Brake,
+                motor_b: DriveCommand::Brake,
+                standby_enabled: true,
+            }
+        );
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_tb6612fng_emergency_stop_error() {
+        let motor_a = mock_motor(
+            &[PinTransaction::set(High).with_error(MockError::Io(ErrorKind::NotConnected))],
+            &[],
+            &[],
+            DriveCommand::forward_const(100),
+        );
+        let motor_b = mock_motor(
+            &[PinTransaction::set(High)],
+            &[PinTransaction::set(High)],
+            &[PwmTransaction::set_duty_cycle(0)],
+            DriveCommand::backward_const(100),
+        );
+        let mut controller = mock_controller(motor_a, motor_b, &[PinTransaction::set(Low)], false);
+
+        // motor B and the standby are handled even though motor A failed
+        assert_eq!(
+            controller.emergency_stop(),
+            Err(Tb6612fngControlError::MotorA(MotorError::In1Error(
+                MockError::Io(ErrorKind::NotConnected)
+            )))
+        );
+        assert!(controller.standby_enabled());
+
+        controller_done(controller);
+    }
+
+    #[test]
+    fn test_drive_command_from_signed_speed_saturating() {
+        assert_eq!(
+            DriveCommand::from_signed_speed_saturating(i8::MAX),
+            DriveCommand::forward_const(100)
+        );
+        assert_eq!(
+            DriveCommand::from_signed_speed_saturating(100),
+            DriveCommand::forward_const(100)
+        );
+        assert_eq!(
+            DriveCommand::from_signed_speed_saturating(1),
+            DriveCommand::forward_const(1)
+        );
+        assert_eq!(
+            DriveCommand::from_signed_speed_saturating(0),
+            DriveCommand::Stop
+        );
+        assert_eq!(
+            DriveCommand::from_signed_speed_saturating(-1),
+            DriveCommand::backward_const(1)
+        );
+        assert_eq!(
+            DriveCommand::from_signed_speed_saturating(i8::MIN),
+            DriveCommand::backward_const(100)
+        );
+    }
+
+    #[cfg(feature = "log")]
+    mod log_capture {
+        use core::cell::Cell;
+        use log::{Log, Metadata, Record};
+
+        super::std::thread_local! {
+            static RECORDS: Cell<usize> = const { Cell::new(0) };
+        }
+
+        struct CountingLogger;
+
+        impl Log for CountingLogger {
+            fn enabled(&self, _metadata: &Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, _record: &Record) {
+                RECORDS.with(|r| r.set(r.get() + 1));
+            }
+
+            fn flush(&self) {}
+        }
+
+        static LOGGER: CountingLogger = CountingLogger;
+
+        /// Install the logger (if not yet done) and return the number of records logged by the current thread.
+        pub fn records() -> usize {
+            let _ = log::set_logger(&LOGGER);
+            log::set_max_level(log::LevelFilter::Trace);
+            RECORDS.with(Cell::get)
+        }
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn test_drive_logs_once() {
+        let mut motor = mock_motor(
+            &[PinTransaction::set(High)],
+            &[PinTransaction::set(Low)],
+            &[PwmTransaction::set_duty_cycle(50)],
+            DriveCommand::Stop,
+        );
+        let before = log_capture::records();
+
+        motor.drive(DriveCommand::forward_const(50)).unwrap();
+
+        // with `defmt` enabled the message goes to `defmt`, nothing is logged twice via `log`
+        let expected = if cfg!(feature = "defmt") {
+            before
+        } else {
+            before + 1
+        };
+        assert_eq!(log_capture::records(), expected);
+
+        motor_done(&mut motor);
+    }
+
+    #[test]
+    fn test_motor_reversal_count() {
+        let mut motor = mock_motor(
+            &[
+                PinTransaction::set(High),
+                PinTransaction::set(Low),
+                PinTransaction::set(High),
+                PinTransaction::set(Low),
+                PinTransaction::set(High),
+            ],
+            &[
+                PinTransaction::set(Low),
+                PinTransaction::set(High),
+                PinTransaction::set(Low),
+            ],
+            &[
+                PwmTransaction::set_duty_cycle(50),
+                PwmTransaction::set_duty_cycle(80),
+                PwmTransaction::set_duty_cycle(0),
+                PwmTransaction::set_duty_cycle(50),
+                PwmTransaction::set_duty_cycle(0),
+                PwmTransaction::set_duty_cycle(30),
+                PwmTransaction::set_duty_cycle(0),
+                PwmTransaction::set_duty_cycle(30),
+            ],
+            DriveCommand::Stop,
+        );
+        assert_eq!(motor.reversal_count(), 0);
+
+        // the first direction and speed changes are no reversals
+        motor.drive(DriveCommand::forward_const(50)).unwrap();
+        motor.drive(DriveCommand::forward_const(80)).unwrap();
+        assert_eq!(motor.reversal_count(), 0);
+
+        motor.drive(DriveCommand::backward_const(50)).unwrap();
+        assert_eq!(motor.reversal_count(), 1);
+
+        // braking in-between does not hide the reversal
+        motor.drive(DriveCommand::Brake).unwrap();
+        motor.drive(DriveCommand::backward_const(30)).unwrap();
+        assert_eq!(motor.reversal_count(), 1);
+
+        motor.reset_reversal_count();
+        assert_eq!(motor.reversal_count(), 0);
+
+        motor.drive(DriveCommand::forward_const(30)).unwrap();
+        assert_eq!(motor.reversal_count(), 1);
+
+        motor_done(&mut motor);
+    }
+
+    /// A fixed-size buffer to test the `ufmt` implementations without allocating.
+    #[cfg(feature = "ufmt")]
+    struct UfmtBuffer {
+        buffer: [u8; 64],
+        len: usize,
+    }
+
+    #[cfg(feature = "ufmt")]
+    impl UfmtBuffer {
+        fn new() -> Self {
+            Self {
+                buffer: [0; 64],
+                len: 0,
+            }
+        }
+
+        fn as_str(&self) -> &str {
+            core::str::from_utf8(&self.buffer[..self.len]).unwrap()
+        }
+    }
+
+    #[cfg(feature = "ufmt")]
+    impl ufmt::uWrite for UfmtBuffer {
+        type Error = ();
+
+        fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+            let end = self.len + s.len();
+            self.buffer
+                .get_mut(self.len..end)
+                .ok_or(())?
+                .copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "ufmt")]
+    #[test]
+    fn test_ufmt() {
+        fn udisplay(value: &impl ufmt::uDisplay) -> UfmtBuffer {
+            let mut buffer = UfmtBuffer::new();
+            ufmt::uwrite!(&mut buffer, "{}", value).unwrap();
+            buffer
+        }
+        fn udebug(value: &impl ufmt::uDebug) -> UfmtBuffer {
+            let mut buffer = UfmtBuffer::new();
+            ufmt::uwrite!(&mut buffer, "{:?}", value).unwrap();
+            buffer
+        }
+
+        for drive_command in [
+            DriveCommand::forward_const(75),
+            DriveCommand::backward_const(5),
+            DriveCommand::Brake,
+            DriveCommand::Stop,
+        ] {
+            assert_eq!(
+                udisplay(&drive_command).as_str(),
+                std::format!("{drive_command}")
+            );
+            assert_eq!(
+                udebug(&drive_command).as_str(),
+                std::format!("{drive_command:?}")
+            );
+        }
+
+        let errors: [MotorError<u8, u8, u8>; 4] = [
+            MotorError::InvalidSpeed,
+            MotorError::In1Error(1),
+            MotorError::In2Error(2),
+            MotorError::PwmError(3),
+        ];
+        for error in errors {
+            assert_eq!(udisplay(&error).as_str(), std::format!("{error}"));
+            assert_eq!(udebug(&error).as_str(), std::format!("{error:?}"));
+        }
+
+        let error = Tb6612fngError::Standby(4u8);
+        assert_eq!(udisplay(&error).as_str(), std::format!("{error}"));
+        assert_eq!(udebug(&error).as_str(), std::format!("{error:?}"));
+    }
+
+    #[test]
+    fn test_tb6612fng_drive_both() {
+        let motor_a = mock_motor(
+            &[PinTransaction::set(Low)],
+            &[PinTransaction::set(High)],
+            &[PwmTransaction::set_duty_cycle(60)],
+            DriveCommand::Stop,
+        );
+        let motor_b = mock_motor(
+            &[PinTransaction::set(Low)],
+            &[PinTransaction::set(High).with_error(MockError::Io(ErrorKind::NotConnected))],
+            &[],
+            DriveCommand::Stop,
+        );
+        let mut controller = mock_controller(motor_a, motor_b, &[], false);
+
+        assert_eq!(
+            controller.drive_both(DriveCommand::backward_const(60)),
+            Err(Tb6612fngDriveError::MotorB(MotorError::In2Error(
+                MockError::Io(ErrorKind::NotConnected)
+            )))
+        );
+        assert_eq!(
+            *controller.motor_a.current_drive_command(),
+            DriveCommand::backward_const(60)
+        );
+        assert_eq!(
+            *controller.motor_b.current_drive_command(),
+            DriveCommand::Stop
+        );
+
+        controller_done(controller);
+    }
+
+    /// Creates a differential drive whose motors expect to be driven with the defined commands.
+    fn mock_differential_drive(
+        left: DriveCommand,
+        right: DriveCommand,
+        left_inverted: bool,
+        right_inverted: bool,
+    ) -> DifferentialDrive<PinMock, PinMock, PwmMock, PinMock, PinMock, PwmMock, PinMock> {
+        fn expecting(drive_command: DriveCommand) -> Motor<PinMock, PinMock, PwmMock> {
+            let state = |high| if high { High } else { Low };
+            let (in1, in2) = drive_command.to_pin_states();
+            let duty = drive_command.magnitude().into();
+            // full speed is set via `set_duty_cycle_fully_on`, which reads the maximum duty cycle
+            let pwm = if duty == 100 {
+                std::vec![
+                    PwmTransaction::max_duty_cycle(100),
+                    PwmTransaction::set_duty_cycle(duty),
+                ]
+            } else {
+                std::vec![PwmTransaction::set_duty_cycle(duty)]
+            };
+            mock_motor(
+                &[PinTransaction::set(state(in1))],
+                &[PinTransaction::set(state(in2))],
+                &pwm,
+                DriveCommand::Stop,
+            )
+        }
+
+        DifferentialDrive::new(
+            mock_controller(expecting(left), expecting(right), &[], false),
+            left_inverted,
+            right_inverted,
+        )
+    }
+
+    #[test]
+    fn test_differential_drive() {
+        type Drive =
+            DifferentialDrive<PinMock, PinMock, PwmMock, PinMock, PinMock, PwmMock, PinMock>;
+        type Action = fn(&mut Drive);
+
+        // (action, left, right, left inverted, right inverted)
+        let cases: [(Action, DriveCommand, DriveCommand, bool, bool); 9] = [
+            (
+                |d| d.drive_tank(30, -60).unwrap(),
+                DriveCommand::forward_const(30),
+                DriveCommand::backward_const(60),
+                false,
+                false,
+            ),
+            (
+                |d| d.drive_tank(i8::MAX, 0).unwrap(),
+                DriveCommand::forward_const(100),
+                DriveCommand::Stop,
+                false,
+                false,
+            ),
+            (
+                |d| d.drive_arcade(50, 20).unwrap(),
+                DriveCommand::forward_const(70),
+                DriveCommand::forward_const(30),
+                false,
+                false,
+            ),
+            (
+                |d| d.drive_arcade(-80, -40).unwrap(),
+                DriveCommand::backward_const(100),
+                DriveCommand::backward_const(40),
+                false,
+                false,
+            ),
+            (
+                |d| d.spin_in_place(40).unwrap(),
+                DriveCommand::forward_const(40),
+                DriveCommand::backward_const(40),
+                false,
+                false,
+            ),
+            (
+                |d| d.spin_in_place(-40).unwrap(),
+                DriveCommand::backward_const(40),
+                DriveCommand::forward_const(40),
+                false,
+                false,
+            ),
+            (
+                |d| d.drive_straight(-25).unwrap(),
+                DriveCommand::backward_const(25),
+                DriveCommand::backward_const(25),
+                false,
+                false,
+            ),
+            // inverted motors
+            (
+                |d| d.drive_straight(25).unwrap(),
+                DriveCommand::forward_const(25),
+                DriveCommand::backward_const(25),
+                false,
+                true,
+            ),
+            (
+                |d| d.drive_tank(i8::MIN, 10).unwrap(),
+                DriveCommand::forward_const(100),
+                DriveCommand::forward_const(10),
+                true,
+                false,
+            ),
+        ];
+
+        for (action, left, right, left_inverted, right_inverted) in cases {
+            let mut drive = mock_differential_drive(left, right, left_inverted, right_inverted);
+
+            action(&mut drive);
+
+            assert_eq!(*drive.controller.motor_a.current_drive_command(), left);
+            assert_eq!(*drive.controller.motor_b.current_drive_command(), right);
+
+            controller_done(drive.release());
+        }
+    }
+
+    #[test]
+    fn test_motor_replace_pwm() {
+        let mut motor = mock_motor(
+            &[PinTransaction::set(High)],
+            &[PinTransaction::set(Low)],
+            &[],
+            DriveCommand::forward_const(50),
+        );
+        let new_pwm = PwmMock::new(&[
+            PwmTransaction::max_duty_cycle(1000),
+            PwmTransaction::set_duty_cycle(500),
+        ]);
+
+        let mut old_pwm = motor.replace_pwm(new_pwm);
+        assert_eq!(motor.max_duty(), 1000);
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(50)
+        );
 
-        Ok(motor)
+        motor.init().unwrap();
+        assert_eq!(motor.current_duty_raw(), 500);
+
+        old_pwm.done();
+        motor_done(&mut motor);
     }
 
-    /// Drive with the defined speed (or brake or stop the motor).
-    ///
-    /// # Errors
-    /// If the underlying pin interaction fails this error will be propagated up.
-    /// The error is specific to your HAL.
-    ///
-    /// The specified speed must be between 0 and 100 (inclusive), otherwise you will get a
-    /// [`MotorError::InvalidSpeed`] error.
-    #[allow(clippy::type_complexity)]
-    pub fn drive(
-        &mut self,
-        drive_command: DriveCommand,
-    ) -> Result<(), MotorError<IN1::Error, IN2::Error, PWM::Error>> {
-        let speed = match drive_command {
-            DriveCommand::Forward(s) | DriveCommand::Backward(s) => s,
-            _ => 0,
-        };
+    #[test]
+    fn test_ramping_motor_steps() {
+        // (step, number of calls to ramp from 0 to 100)
+        let cases = [(10, 10), (30, 4), (100, 1), (150, 1)];
 
-        if speed > 100 {
-            return Err(MotorError::InvalidSpeed);
-        }
+        for (step, calls) in cases {
+            // the direction pins are only set once
+            let in1 = [PinTransaction::set(High)];
+            let in2 = [PinTransaction::set(Low)];
+            let mut pwm: std::vec::Vec<_> = (1..=calls)
+                .map(|i| PwmTransaction::set_duty_cycle((i * step).min(100)))
+                .collect();
+            pwm.insert(pwm.len() - 1, PwmTransaction::max_duty_cycle(100));
+            let mut motor =
+                RampingMotor::new(mock_motor(&in1, &in2, &pwm, DriveCommand::Stop), step as u8);
+            assert!(motor.is_target_reached());
 
-        match drive_command {
-            DriveCommand::Forward(_) => {
-                self.in1.set_high().map_err(MotorError::In1Error)?;
-                self.in2.set_low().map_err(MotorError::In2Error)?;
-            }
-            DriveCommand::Backward(_) => {
-                self.in1.set_low().map_err(MotorError::In1Error)?;
-                self.in2.set_high().map_err(MotorError::In2Error)?;
-            }
-            DriveCommand::Brake => {
-                self.in1.set_high().map_err(MotorError::In1Error)?;
-                self.in2.set_high().map_err(MotorError::In2Error)?;
-            }
-            DriveCommand::Stop => {
-                self.in1.set_low().map_err(MotorError::In1Error)?;
-                self.in2.set_low().map_err(MotorError::In2Error)?;
+            for i in 1..=calls {
+                motor.drive(DriveCommand::forward_const(100)).unwrap();
+                assert_eq!(motor.is_target_reached(), i == calls);
             }
+            assert_eq!(motor.target(), Some(DriveCommand::forward_const(100)));
+            assert_eq!(
+                *motor.current_drive_command(),
+                DriveCommand::forward_const(100)
+            );
+
+            motor_done(motor.inner_mut());
         }
+    }
 
-        self.pwm
-            .set_duty_cycle_percent(speed)
-            .map_err(MotorError::PwmError)?;
+    #[test]
+    fn test_ramping_motor_reverse() {
+        let mut motor = RampingMotor::new(
+            mock_motor(
+                &[PinTransaction::set(Low), PinTransaction::set(High)],
+                &[PinTransaction::set(Low), PinTransaction::set(High)],
+                &[
+                    PwmTransaction::set_duty_cycle(0),
+                    PwmTransaction::set_duty_cycle(20),
+                    PwmTransaction::set_duty_cycle(0),
+                ],
+                DriveCommand::forward_const(20),
+            ),
+            20,
+        );
 
-        self.current_drive_command = drive_command;
+        motor.drive(DriveCommand::backward_const(40)).unwrap();
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+        motor.drive(DriveCommand::backward_const(40)).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(20)
+        );
 
-        Ok(())
+        // braking is applied once the speed reaches 0
+        motor.drive(DriveCommand::Brake).unwrap();
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Brake);
+        assert!(motor.is_target_reached());
+
+        motor_done(motor.inner_mut());
     }
 
-    /// Get the currently active drive command.
-    ///
-    /// If you only want to know the speed consider calling [`Motor::current_speed`] instead.
-    pub fn current_drive_command(&self) -> &DriveCommand {
-        &self.current_drive_command
+    #[test]
+    fn test_ramping_motor_deadband() {
+        let inner = mock_motor(
+            &[PinTransaction::set(Low), PinTransaction::set(High)],
+            &[PinTransaction::set(Low)],
+            &[
+                PwmTransaction::set_duty_cycle(0),
+                PwmTransaction::set_duty_cycle(20),
+                PwmTransaction::set_duty_cycle(30),
+            ],
+            DriveCommand::Stop,
+        );
+        let mut motor = RampingMotor::new(DeadbandMotor::new(inner, 15), 10);
+
+        // the first step is within the deadband, the ramp continues nonetheless
+        motor.drive(DriveCommand::forward_const(30)).unwrap();
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+        assert!(!motor.is_target_reached());
+        motor.drive(DriveCommand::forward_const(30)).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(20)
+        );
+        motor.drive(DriveCommand::forward_const(30)).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(30)
+        );
+        assert!(motor.is_target_reached());
+
+        motor_done(motor.inner_mut().inner_mut());
     }
 
-    /// Return the current speed of the motor (in percentage). Note that driving forward returns a positive number
-    /// while driving backward returns a negative number and both [`DriveCommand::Brake`] and [`DriveCommand::Stop`] return 0.
-    ///
-    /// If you need to know in more details what the current status is, consider calling [`Motor::current_drive_command`] instead.
-    pub fn current_speed(&self) -> i8 {
-        match self.current_drive_command() {
-            DriveCommand::Forward(s) => *s as i8,
-            DriveCommand::Backward(s) => -(*s as i8),
-            DriveCommand::Brake => 0,
-            DriveCommand::Stop => 0,
-        }
+    #[test]
+    fn test_ramping_motor_max_speed() {
+        let mut inner = mock_motor(
+            &[
+                PinTransaction::set(High),
+                PinTransaction::set(Low),
+                PinTransaction::set(High),
+            ],
+            &[PinTransaction::set(Low)],
+            &[
+                PwmTransaction::set_duty_cycle(50),
+                PwmTransaction::set_duty_cycle(80),
+                PwmTransaction::set_duty_cycle(0),
+                PwmTransaction::set_duty_cycle(50),
+            ],
+            DriveCommand::Stop,
+        );
+        inner.set_max_speed(80);
+        let mut motor = RampingMotor::new(inner, 50);
+
+        motor.drive(DriveCommand::forward_const(100)).unwrap();
+        assert!(!motor.is_target_reached());
+        motor.drive(DriveCommand::forward_const(100)).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(80)
+        );
+        assert!(motor.is_target_reached());
+
+        // driving the wrapped motor directly restarts the ramp from its command
+        motor.inner_mut().drive(DriveCommand::Stop).unwrap();
+        assert!(!motor.is_target_reached());
+        motor.drive(DriveCommand::forward_const(100)).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(50)
+        );
+
+        motor_done(motor.inner_mut());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::{DriveCommand, Motor, MotorError};
-    use embedded_hal_mock::eh1::digital::Mock as PinMock;
-    use embedded_hal_mock::eh1::digital::State::{High, Low};
-    use embedded_hal_mock::eh1::digital::Transaction as PinTransaction;
-    use embedded_hal_mock::eh1::pwm::Mock as PwmMock;
-    use embedded_hal_mock::eh1::pwm::Transaction as PwmTransaction;
+    #[cfg(all(feature = "log", not(feature = "defmt")))]
+    #[test]
+    fn test_standby_logs() {
+        let mut controller = mock_controller(
+            motor_with_drive_command(DriveCommand::forward_const(50)),
+            motor_with_drive_command(DriveCommand::Brake),
+            &[PinTransaction::set(Low), PinTransaction::set(High)],
+            false,
+        );
+        let before = log_capture::records();
+
+        controller.enable_standby().unwrap();
+        assert_eq!(log_capture::records(), before + 1);
+        controller.disable_standby().unwrap();
+        assert_eq!(log_capture::records(), before + 2);
+
+        controller_done(controller);
+    }
 
+    #[cfg(all(feature = "log", feature = "defmt"))]
     #[test]
-    fn test_motor_stop() {
-        let max_duty = 100;
-        let motor_in1_expectations = [PinTransaction::set(Low), PinTransaction::set(Low)];
-        let motor_in2_expectations = [PinTransaction::set(Low), PinTransaction::set(Low)];
-        let motor_pwm_expectations = [
-            PwmTransaction::max_duty_cycle(max_duty),
-            PwmTransaction::set_duty_cycle(0),
-            PwmTransaction::max_duty_cycle(max_duty),
-            PwmTransaction::set_duty_cycle(0),
-        ];
-        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
-        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
-        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+    fn test_standby_logs_only_via_defmt() {
+        let mut controller = mock_controller(
+            motor_with_drive_command(DriveCommand::forward_const(50)),
+            motor_with_drive_command(DriveCommand::Brake),
+            &[PinTransaction::set(Low), PinTransaction::set(High)],
+            false,
+        );
+        let before = log_capture::records();
 
-        let mut motor =
-            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+        controller.enable_standby().unwrap();
+        controller.disable_standby().unwrap();
+        assert_eq!(log_capture::records(), before);
 
-        motor.drive(DriveCommand::Stop).unwrap();
+        controller_done(controller);
+    }
 
-        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
-        assert_eq!(motor.current_speed(), 0);
+    #[test]
+    fn test_deadband_motor() {
+        // (deadband, drive command, expected drive command)
+        let cases = [
+            (15, DriveCommand::forward_const(14), DriveCommand::Stop),
+            (
+                15,
+                DriveCommand::forward_const(15),
+                DriveCommand::forward_const(15),
+            ),
+            (
+                15,
+                DriveCommand::forward_const(16),
+                DriveCommand::forward_const(16),
+            ),
+            (15, DriveCommand::backward_const(14), DriveCommand::Stop),
+            (
+                15,
+                DriveCommand::backward_const(15),
+                DriveCommand::backward_const(15),
+            ),
+            (
+                15,
+                DriveCommand::backward_const(16),
+                DriveCommand::backward_const(16),
+            ),
+            (15, DriveCommand::Brake, DriveCommand::Brake),
+            (15, DriveCommand::Stop, DriveCommand::Stop),
+            // a deadband of 0 disables it
+            (
+                0,
+                DriveCommand::forward_const(1),
+                DriveCommand::forward_const(1),
+            ),
+        ];
 
-        motor_in1.done();
-        motor_in2.done();
-        motor_pwm.done();
+        for (deadband, drive_command, expected) in cases {
+            let state = |high| if high { High } else { Low };
+            let (in1, in2) = expected.to_pin_states();
+            let mut motor = DeadbandMotor::new(
+                mock_motor(
+                    &[PinTransaction::set(state(in1))],
+                    &[PinTransaction::set(state(in2))],
+                    &[PwmTransaction::set_duty_cycle(expected.magnitude().into())],
+                    DriveCommand::forward_const(50),
+                ),
+                deadband,
+            );
+
+            motor.drive(drive_command).unwrap();
+            assert_eq!(*motor.current_drive_command(), expected);
+
+            motor_done(motor.inner_mut());
+        }
     }
 
     #[test]
-    fn test_motor_brake() {
-        let max_duty = 100;
-        let motor_in1_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
-        let motor_in2_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
-        let motor_pwm_expectations = [
-            PwmTransaction::max_duty_cycle(max_duty),
-            PwmTransaction::set_duty_cycle(0),
-            PwmTransaction::max_duty_cycle(max_duty),
-            PwmTransaction::set_duty_cycle(0),
+    fn test_reversed_motor() {
+        // (drive command, drive command applied to the motor, in1, in2, duty)
+        let cases = [
+            (
+                DriveCommand::forward_const(30),
+                DriveCommand::backward_const(30),
+                Low,
+                High,
+                30,
+            ),
+            (
+                DriveCommand::backward_const(30),
+                DriveCommand::forward_const(30),
+                High,
+                Low,
+                30,
+            ),
+            (DriveCommand::Brake, DriveCommand::Brake, High, High, 0),
+            (DriveCommand::Stop, DriveCommand::Stop, Low, Low, 0),
         ];
-        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
-        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
-        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
 
-        let mut motor =
-            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+        for (drive_command, applied, in1, in2, duty) in cases {
+            let mut motor = ReversedMotor::new(mock_motor(
+                &[PinTransaction::set(in1)],
+                &[PinTransaction::set(in2)],
+                &[PwmTransaction::set_duty_cycle(duty)],
+                DriveCommand::forward_const(50),
+            ));
+            assert_eq!(
+                *motor.current_drive_command(),
+                DriveCommand::backward_const(50)
+            );
 
-        motor.drive(DriveCommand::Brake).unwrap();
+            motor.drive(drive_command).unwrap();
+            assert_eq!(*motor.current_drive_command(), drive_command);
+            assert_eq!(
+                DriveMotor::current_speed(&motor),
+                drive_command.signed_speed()
+            );
 
-        assert_eq!(*motor.current_drive_command(), DriveCommand::Brake);
-        assert_eq!(motor.current_speed(), 0);
+            let mut motor = motor.release();
+            assert_eq!(*motor.current_drive_command(), applied);
+            motor_done(&mut motor);
+        }
+    }
 
-        motor_in1.done();
-        motor_in2.done();
-        motor_pwm.done();
+    #[test]
+    fn test_reversed_motor_double_wrap() {
+        let mut motor = ReversedMotor::new(ReversedMotor::new(mock_motor(
+            &[PinTransaction::set(High)],
+            &[PinTransaction::set(Low)],
+            &[PwmTransaction::set_duty_cycle(30)],
+            DriveCommand::backward_const(50),
+        )));
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(50)
+        );
+
+        motor.drive(DriveCommand::forward_const(30)).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(30)
+        );
+
+        let mut motor = motor.release().release();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(30)
+        );
+        motor_done(&mut motor);
     }
 
     #[test]
-    fn test_motor_drive_forward() {
-        let max_duty = 100;
-        let speed: u8 = 100;
-        let motor_in1_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
-        let motor_in2_expectations = [PinTransaction::set(Low), PinTransaction::set(Low)];
-        let motor_pwm_expectations = [
-            PwmTransaction::max_duty_cycle(max_duty),
-            PwmTransaction::set_duty_cycle(0),
-            PwmTransaction::max_duty_cycle(max_duty),
-            PwmTransaction::set_duty_cycle(speed as u16),
-        ];
-        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
-        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
-        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+    fn test_reversed_motor_reports_applied_command() {
+        let mut motor = ReversedMotor::new(RampingMotor::new(
+            mock_motor(
+                &[PinTransaction::set(Low)],
+                &[PinTransaction::set(High)],
+                &[PwmTransaction::set_duty_cycle(10)],
+                DriveCommand::Stop,
+            ),
+            10,
+        ));
 
-        let mut motor =
-            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+        // the wrapped motor only applies the first step of the ramp
+        motor.drive(DriveCommand::forward_const(50)).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(10)
+        );
+        assert_eq!(DriveMotor::current_speed(&motor), 10);
+        assert_eq!(
+            *motor.inner().current_drive_command(),
+            DriveCommand::backward_const(10)
+        );
 
-        motor
-            .drive(DriveCommand::Forward(speed))
-            .expect("speed can be set");
+        motor_done(motor.release().inner_mut());
+    }
 
-        assert_eq!(*motor.current_drive_command(), DriveCommand::Forward(100));
-        assert_eq!(motor.current_speed(), speed as i8);
+    #[test]
+    fn test_motor_deadband() {
+        let mut motor = mock_motor(
+            &[PinTransaction::set(Low)],
+            &[PinTransaction::set(Low), PinTransaction::set(High)],
+            &[
+                PwmTransaction::set_duty_cycle(0),
+                PwmTransaction::set_duty_cycle(10),
+            ],
+            DriveCommand::forward_const(50),
+        );
+        motor.set_deadband(10);
+        assert_eq!(motor.deadband(), 10);
 
-        motor_in1.done();
-        motor_in2.done();
-        motor_pwm.done();
+        motor.drive(DriveCommand::forward_const(9)).unwrap();
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+        assert_eq!(motor.current_speed(), 0);
+
+        motor.drive(DriveCommand::backward_const(10)).unwrap();
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(10)
+        );
+
+        motor_done(&mut motor);
     }
 
     #[test]
-    fn test_motor_drive_backward() {
-        let max_duty = 100;
-        let speed = 100;
-        let motor_in1_expectations = [PinTransaction::set(Low), PinTransaction::set(Low)];
-        let motor_in2_expectations = [PinTransaction::set(Low), PinTransaction::set(High)];
-        let motor_pwm_expectations = [
-            PwmTransaction::max_duty_cycle(max_duty),
-            PwmTransaction::set_duty_cycle(0),
-            PwmTransaction::max_duty_cycle(max_duty),
-            PwmTransaction::set_duty_cycle(speed as u16),
-        ];
-        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
-        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
-        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+    fn test_motor_ramp_to_deadband() {
+        let mut motor = mock_motor(
+            &[PinTransaction::set(High), PinTransaction::set(Low)],
+            &[PinTransaction::set(Low), PinTransaction::set(High)],
+            &[
+                PwmTransaction::set_duty_cycle(15),
+                PwmTransaction::set_duty_cycle(20),
+                PwmTransaction::set_duty_cycle(0),
+                PwmTransaction::set_duty_cycle(15),
+            ],
+            DriveCommand::Stop,
+        );
+        motor.set_deadband(15);
 
-        let mut motor =
-            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+        // accelerating jumps to the edge of the deadband
+        assert!(!motor.ramp_to(DriveCommand::forward_const(20), 10).unwrap());
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(15)
+        );
+        assert!(motor.ramp_to(DriveCommand::forward_const(20), 10).unwrap());
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::forward_const(20)
+        );
 
-        motor
-            .drive(DriveCommand::Backward(speed))
-            .expect("speed can be set");
+        // decelerating stops within the deadband
+        assert!(!motor.ramp_to(DriveCommand::backward_const(15), 10).unwrap());
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+        assert!(motor.ramp_to(DriveCommand::backward_const(15), 10).unwrap());
+        assert_eq!(
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(15)
+        );
 
-        assert_eq!(*motor.current_drive_command(), DriveCommand::Backward(100));
-        assert_eq!(motor.current_speed(), -(speed as i8));
+        motor_done(&mut motor);
+    }
 
-        motor_in1.done();
-        motor_in2.done();
-        motor_pwm.done();
+    #[test]
+    fn test_emergency_stop_trait() {
+        fn stop<T: EmergencyStop>(t: &mut T) -> Result<(), T::Error> {
+            t.emergency_stop()
+        }
+
+        let mut motor = mock_motor(
+            &[PinTransaction::set(High)],
+            &[PinTransaction::set(High)],
+            &[PwmTransaction::set_duty_cycle(0)],
+            DriveCommand::forward_const(100),
+        );
+        stop(&mut motor).unwrap();
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Brake);
+        motor_done(&mut motor);
+
+        let motor_a = mock_motor(
+            &[PinTransaction::set(High)],
+            &[PinTransaction::set(High)],
+            &[PwmTransaction::set_duty_cycle(0)],
+            DriveCommand::backward_const(20),
+        );
+        let motor_b = mock_motor(
+            &[PinTransaction::set(High)],
+            &[PinTransaction::set(High)],
+            &[PwmTransaction::set_duty_cycle(0)],
+            DriveCommand::Stop,
+        );
+        let mut controller = mock_controller(motor_a, motor_b, &[PinTransaction::set(Low)], false);
+        stop(&mut controller).unwrap();
+        assert!(controller.standby_enabled());
+        controller_done(controller);
     }
 
     #[test]
-    fn test_motor_drive_invalid_speed() {
-        let max_duty = 100;
-        let motor_in1_expectations = [PinTransaction::set(Low)];
-        let motor_in2_expectations = [PinTransaction::set(Low)];
-        let motor_pwm_expectations = [
-            PwmTransaction::max_duty_cycle(max_duty),
-            PwmTransaction::set_duty_cycle(0),
-        ];
-        let mut motor_in1 = PinMock::new(&motor_in1_expectations);
-        let mut motor_in2 = PinMock::new(&motor_in2_expectations);
-        let mut motor_pwm = PwmMock::new(&motor_pwm_expectations);
+    fn test_tb6612fng_control_error_from() {
+        type E = Tb6612fngControlError<MotorError<u8, u8, u8>, MotorError<u16, u16, u16>, u32>;
 
-        let mut motor =
-            Motor::new(motor_in1.clone(), motor_in2.clone(), motor_pwm.clone()).unwrap();
+        assert_eq!(E::from(Tb6612fngError::Standby(1)), E::Standby(1));
+        assert_eq!(
+            E::from(Tb6612fngDriveError::MotorA(MotorError::In1Error(2))),
+            E::MotorA(MotorError::In1Error(2))
+        );
+        assert_eq!(
+            E::from(Tb6612fngDriveError::MotorB(MotorError::PwmError(3))),
+            E::MotorB(MotorError::PwmError(3))
+        );
+        assert_eq!(E::from(Tb6612fngDriveError::InStandby), E::InStandby);
+        assert_eq!(E::from(Tb6612fngDriveError::InvalidSpeed), E::InvalidSpeed);
+    }
 
-        let current_drive_command = *motor.current_drive_command();
-        let current_speed = motor.current_speed();
+    #[test]
+    fn test_motor_new_with_state() {
+        let in1 = PinMock::new(&[]);
+        let in2 = PinMock::new(&[]);
+        let pwm = PwmMock::new(&[PwmTransaction::max_duty_cycle(200)]);
 
+        let mut motor = Motor::new_with_state(in1, in2, pwm, DriveCommand::backward_const(40));
         assert_eq!(
-            motor
-                .drive(DriveCommand::Forward(101))
-                .expect_err("Invalid speed must result in an exception"),
-            MotorError::InvalidSpeed
+            *motor.current_drive_command(),
+            DriveCommand::backward_const(40)
         );
+        assert_eq!(motor.current_duty_raw(), 80);
 
-        // this should still be what was set before the invalid command
-        assert_eq!(*motor.current_drive_command(), current_drive_command);
-        assert_eq!(motor.current_speed(), current_speed);
+        motor_done(&mut motor);
+    }
 
-        motor_in1.done();
-        motor_in2.done();
-        motor_pwm.done();
+    #[test]
+    fn test_tb6612fng_motor_accessors() {
+        let motor_a = mock_motor(
+            &[PinTransaction::set(High)],
+            &[PinTransaction::set(Low)],
+            &[PwmTransaction::set_duty_cycle(50)],
+            DriveCommand::Stop,
+        );
+        let motor_b = motor_with_drive_command(DriveCommand::Stop);
+        let mut controller = mock_controller(
+            motor_a,
+            motor_b,
+            &[PinTransaction::set(High), PinTransaction::set(Low)],
+            true,
+        );
+
+        assert!(controller.motor_a().is_none());
+        assert!(controller.motor_b().is_none());
+
+        controller.disable_standby().unwrap();
+        controller
+            .motor_a()
+            .unwrap()
+            .drive(DriveCommand::forward_const(50))
+            .unwrap();
+        assert!(controller.motor_b().is_some());
+
+        controller.enable_standby().unwrap();
+        assert!(controller.motor_a().is_none());
+
+        controller_done(controller);
     }
 }
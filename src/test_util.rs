@@ -0,0 +1,376 @@
+//! Fake pins which record their outputs and a fake motor, for testing code built on top of this driver.
+//!
+//! Instead of listing the expected transactions up front (as with `embedded-hal-mock`) you let your code drive the
+//! motor and assert on the recorded history afterwards. As the [`Motor`](crate::Motor) takes ownership of its pins you
+//! can pass mutable references to them, which implement the `embedded-hal` traits as well.
+//!
+//! The history is stored in a fixed-size buffer (so that this works in `no_std` environments), recording more than
+//! `N` values panics.
+//!
+//! # Usage example
+//! ```
+//! # use tb6612fng::{DriveCommand, Motor};
+//! # use tb6612fng::test_util::{RecordingPin, RecordingPwm};
+//! let mut in1 = RecordingPin::<8>::new();
+//! let mut in2 = RecordingPin::<8>::new();
+//! let mut pwm = RecordingPwm::<8>::new(100);
+//!
+//! let mut motor = Motor::new(&mut in1, &mut in2, &mut pwm).unwrap();
+//! motor.drive(DriveCommand::forward_const(50)).unwrap();
+//! motor.drive(DriveCommand::Brake).unwrap();
+//! drop(motor);
+//!
+//! // `new` stops the motor, then it drives forward and finally brakes (pins are only set when they change)
+//! assert_eq!(in1.states(), &[false, true]);
+//! assert_eq!(in2.states(), &[false, true]);
+//! assert_eq!(pwm.duty_cycles(), &[0, 50, 0]);
+//! ```
+//!
+//! If your code is generic over [`DriveMotor`] you can use [`MockDriveMotor`] instead, which records the drive
+//! commands themselves.
+
+use crate::{DriveCommand, DriveMotor};
+use core::convert::Infallible;
+use core::error::Error;
+use core::fmt::Formatter;
+use embedded_hal::digital::{OutputPin, StatefulOutputPin};
+use embedded_hal::pwm::SetDutyCycle;
+
+/// A fixed-capacity buffer recording up to `N` values.
+#[derive(Debug, Clone)]
+struct History<T, const N: usize> {
+    values: [T; N],
+    len: usize,
+}
+
+impl<T: Copy + Default, const N: usize> History<T, N> {
+    fn new() -> Self {
+        Self {
+            values: [T::default(); N],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, value: T) {
+        assert!(self.len < N, "the history is full (capacity: {N})");
+        self.values[self.len] = value;
+        self.len += 1;
+    }
+
+    fn as_slice(&self) -> &[T] {
+        &self.values[..self.len]
+    }
+
+    fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+/// An output pin which records every state it is set to (`true` meaning high).
+///
+/// The pin starts out low. Setting more than `N` states panics.
+#[derive(Debug, Clone)]
+pub struct RecordingPin<const N: usize> {
+    high: bool,
+    history: History<bool, N>,
+}
+
+impl<const N: usize> RecordingPin<N> {
+    /// Create a new pin which is low and has not recorded anything yet.
+    pub fn new() -> Self {
+        Self {
+            high: false,
+            history: History::new(),
+        }
+    }
+
+    /// Returns all states the pin has been set to, in order.
+    pub fn states(&self) -> &[bool] {
+        self.history.as_slice()
+    }
+
+    /// Returns whether the pin is currently high.
+    pub fn is_high(&self) -> bool {
+        self.high
+    }
+
+    /// Forget all recorded states (the current state of the pin is kept).
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+}
+
+impl<const N: usize> Default for RecordingPin<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> embedded_hal::digital::ErrorType for RecordingPin<N> {
+    type Error = Infallible;
+}
+
+impl<const N: usize> OutputPin for RecordingPin<N> {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.high = false;
+        self.history.push(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.high = true;
+        self.history.push(true);
+        Ok(())
+    }
+}
+
+impl<const N: usize> StatefulOutputPin for RecordingPin<N> {
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.high)
+    }
+
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.high)
+    }
+}
+
+/// A PWM pin which records every duty cycle it is set to.
+///
+/// The duty cycle starts out at 0. Setting more than `N` duty cycles panics.
+#[derive(Debug, Clone)]
+pub struct RecordingPwm<const N: usize> {
+    max_duty_cycle: u16,
+    duty_cycle: u16,
+    history: History<u16, N>,
+}
+
+impl<const N: usize> RecordingPwm<N> {
+    /// Create a new PWM pin with the defined [maximum duty cycle](SetDutyCycle::max_duty_cycle).
+    pub fn new(max_duty_cycle: u16) -> Self {
+        Self {
+            max_duty_cycle,
+            duty_cycle: 0,
+            history: History::new(),
+        }
+    }
+
+    /// Returns all duty cycles the pin has been set to, in order.
+    pub fn duty_cycles(&self) -> &[u16] {
+        self.history.as_slice()
+    }
+
+    /// Returns the current duty cycle.
+    pub fn duty_cycle(&self) -> u16 {
+        self.duty_cycle
+    }
+
+    /// Forget all recorded duty cycles (the current duty cycle is kept).
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+}
+
+impl<const N: usize> embedded_hal::pwm::ErrorType for RecordingPwm<N> {
+    type Error = Infallible;
+}
+
+impl<const N: usize> SetDutyCycle for RecordingPwm<N> {
+    fn max_duty_cycle(&self) -> u16 {
+        self.max_duty_cycle
+    }
+
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        self.duty_cycle = duty;
+        self.history.push(duty);
+        Ok(())
+    }
+}
+
+/// The error returned by [`MockDriveMotor`] after calling [`MockDriveMotor::fail_next_drive`].
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+pub struct MockDriveMotorError;
+
+impl core::fmt::Display for MockDriveMotorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "the mock motor has been told to fail")
+    }
+}
+
+impl Error for MockDriveMotorError {}
+
+/// A [`DriveMotor`] which records every drive command it is driven with, for testing code which is generic over
+/// [`DriveMotor`].
+///
+/// Like the [`Motor`](crate::Motor) it accepts speeds up to 100 and starts out [stopped](DriveCommand::Stop). Driving
+/// it more than `N` times panics.
+///
+/// # Usage example
+/// ```
+/// # use tb6612fng::{DriveCommand, DriveMotor};
+/// # use tb6612fng::test_util::MockDriveMotor;
+/// fn full_speed_ahead<M: DriveMotor>(motor: &mut M) -> Result<(), M::Error> {
+///     motor.drive(DriveCommand::forward_const(100))
+/// }
+///
+/// let mut motor = MockDriveMotor::<4>::new();
+/// full_speed_ahead(&mut motor).unwrap();
+/// assert_eq!(motor.drive_commands(), &[DriveCommand::forward_const(100)]);
+///
+/// motor.fail_next_drive();
+/// assert!(full_speed_ahead(&mut motor).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockDriveMotor<const N: usize> {
+    current_drive_command: DriveCommand,
+    history: History<DriveCommand, N>,
+    fail_next_drive: bool,
+}
+
+impl<const N: usize> MockDriveMotor<N> {
+    /// Create a new stopped motor which has not recorded anything yet.
+    pub fn new() -> Self {
+        Self {
+            current_drive_command: DriveCommand::Stop,
+            history: History::new(),
+            fail_next_drive: false,
+        }
+    }
+
+    /// Returns all drive commands the motor has successfully been driven with, in order.
+    pub fn drive_commands(&self) -> &[DriveCommand] {
+        self.history.as_slice()
+    }
+
+    /// Let the next call to [`DriveMotor::drive`] fail with a [`MockDriveMotorError`].
+    pub fn fail_next_drive(&mut self) {
+        self.fail_next_drive = true;
+    }
+
+    /// Forget all recorded drive commands (the current drive command is kept).
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+}
+
+impl<const N: usize> Default for MockDriveMotor<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> DriveMotor for MockDriveMotor<N> {
+    type Error = MockDriveMotorError;
+
+    /// Record the drive command, unless the call has been told to fail.
+    fn drive(&mut self, drive_command: DriveCommand) -> Result<(), Self::Error> {
+        if core::mem::take(&mut self.fail_next_drive) {
+            return Err(MockDriveMotorError);
+        }
+
+        self.current_drive_command = drive_command;
+        self.history.push(drive_command);
+        Ok(())
+    }
+
+    fn current_drive_command(&self) -> &DriveCommand {
+        &self.current_drive_command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Motor, RampingMotor, Tb6612fng};
+
+    #[test]
+    fn test_recording_pin() {
+        let mut pin = RecordingPin::<4>::new();
+        assert!(!pin.is_high());
+
+        pin.set_high().unwrap();
+        pin.set_high().unwrap();
+        pin.set_low().unwrap();
+        assert_eq!(pin.states(), &[true, true, false]);
+        assert!(pin.is_set_low().unwrap());
+
+        pin.clear();
+        assert!(pin.states().is_empty());
+        assert!(!pin.is_high());
+    }
+
+    #[test]
+    #[should_panic(expected = "the history is full")]
+    fn test_recording_pin_full() {
+        let mut pin = RecordingPin::<1>::new();
+        pin.set_high().unwrap();
+        pin.set_low().unwrap();
+    }
+
+    #[test]
+    fn test_recording_pwm_with_controller() {
+        let mut pins = [
+            RecordingPin::<4>::new(),
+            RecordingPin::<4>::new(),
+            RecordingPin::<4>::new(),
+            RecordingPin::<4>::new(),
+        ];
+        let [a_in1, a_in2, b_in1, b_in2] = &mut pins;
+        let mut a_pwm = RecordingPwm::<4>::new(200);
+        let mut b_pwm = RecordingPwm::<4>::new(200);
+        let mut standby = RecordingPin::<4>::new();
+
+        let motor_a = Motor::new(a_in1, a_in2, &mut a_pwm).unwrap();
+        let motor_b = Motor::new(b_in1, b_in2, &mut b_pwm).unwrap();
+        let mut controller = Tb6612fng::new(motor_a, motor_b, &mut standby).unwrap();
+        controller
+            .drive(
+                DriveCommand::forward_const(50),
+                DriveCommand::backward_const(100),
+            )
+            .unwrap();
+        controller.enable_standby().unwrap();
+
+        assert_eq!(pins[0].states(), &[false, true]);
+        assert_eq!(pins[1].states(), &[false]);
+        assert_eq!(pins[2].states(), &[false]);
+        assert_eq!(pins[3].states(), &[false, true]);
+        assert_eq!(a_pwm.duty_cycles(), &[0, 100]);
+        assert_eq!(b_pwm.duty_cycles(), &[0, 200]);
+        assert_eq!(standby.states(), &[true, false]);
+    }
+
+    #[test]
+    fn test_mock_drive_motor() {
+        let mut motor = MockDriveMotor::<4>::new();
+        assert_eq!(*motor.current_drive_command(), DriveCommand::Stop);
+
+        motor.drive(DriveCommand::backward_const(30)).unwrap();
+        assert_eq!(motor.current_speed(), -30);
+
+        motor.fail_next_drive();
+        assert_eq!(motor.drive(DriveCommand::Brake), Err(MockDriveMotorError));
+        motor.drive(DriveCommand::Brake).unwrap();
+
+        assert_eq!(
+            motor.drive_commands(),
+            &[DriveCommand::backward_const(30), DriveCommand::Brake]
+        );
+    }
+
+    #[test]
+    fn test_mock_drive_motor_with_wrapper() {
+        let mut motor = RampingMotor::new(MockDriveMotor::<4>::new(), 40);
+        while !motor.is_target_reached() || motor.target().is_none() {
+            motor.drive(DriveCommand::forward_const(100)).unwrap();
+        }
+
+        assert_eq!(
+            motor.inner().drive_commands(),
+            &[
+                DriveCommand::forward_const(40),
+                DriveCommand::forward_const(80),
+                DriveCommand::forward_const(100)
+            ]
+        );
+    }
+}